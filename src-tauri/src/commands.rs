@@ -1,83 +1,305 @@
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tauri::{Emitter, Manager};
+
+use crate::error::AppError;
 use crate::kubernetes;
+use crate::kubernetes::drain::DrainTasks;
+use crate::kubernetes::pool::ClientPool;
+use crate::kubernetes::subscriptions::Subscriptions;
+use crate::kubernetes::watcher::KubeconfigWatcher;
 use crate::persistence;
 use crate::types::*;
 
+/// Get the pooled client for a cluster
+async fn get_client(app_handle: &tauri::AppHandle, config: &ClusterContext) -> Result<kube::Client, AppError> {
+    app_handle
+        .state::<ClientPool>()
+        .get(config)
+        .await
+        .map_err(AppError::from)
+}
+
 /// Get cluster information
 #[tauri::command]
-pub async fn get_cluster_info(config: ClusterContext) -> Result<ClusterInfo, String> {
-    let client = if let Some(ref kubeconfig) = config.kubeconfig {
-        kubernetes::client::from_kubeconfig(kubeconfig)
-            .await
-            .map_err(|e| format!("Failed to create client: {}", e))?
-    } else {
-        kubernetes::client::from_default()
-            .await
-            .map_err(|e| format!("Failed to create client: {}", e))?
-    };
-    
+pub async fn get_cluster_info(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+) -> Result<ClusterInfo, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
     kubernetes::cluster::get_info(client)
         .await
-        .map_err(|e| e.to_string())
-}
-
-/// Get workloads (pods) for a cluster
-#[tauri::command]
-pub async fn get_workloads(config: ClusterContext) -> WorkloadsResponse {
-    let client = match (
-        config.kubeconfig.as_ref().map(|kc| kubernetes::client::from_kubeconfig(kc)),
-        config.kubeconfig.is_none().then(|| kubernetes::client::from_default())
-    ) {
-        (Some(fut), _) => match fut.await {
-            Ok(c) => c,
-            Err(e) => return WorkloadsResponse {
-                items: vec![],
-                error: Some(format!("Failed to create client: {}", e)),
-            },
-        },
-        (None, Some(fut)) => match fut.await {
-            Ok(c) => c,
-            Err(e) => return WorkloadsResponse {
-                items: vec![],
-                error: Some(format!("Failed to create client: {}", e)),
-            },
-        },
-        _ => return WorkloadsResponse {
+        .map_err(AppError::from)
+}
+
+/// Get node inventory for a cluster
+#[tauri::command]
+pub async fn get_nodes(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+) -> Result<Vec<NodeDetails>, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    kubernetes::nodes::get_nodes(client)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Mark a node unschedulable
+#[tauri::command]
+pub async fn cordon_node(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    node_name: String,
+) -> Result<(), AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    kubernetes::nodes::set_unschedulable(client, &node_name, true)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Mark a node schedulable again
+#[tauri::command]
+pub async fn uncordon_node(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    node_name: String,
+) -> Result<(), AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    kubernetes::nodes::set_unschedulable(client, &node_name, false)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Cordon a node and evict its pods, streaming per-pod progress until done or cancelled
+#[tauri::command]
+pub async fn drain_node(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    node_name: String,
+    options: Option<DrainOptions>,
+    on_progress: Channel<DrainProgress>,
+) -> Result<DrainSummary, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+    let key = drain_key(&config, &node_name);
+
+    let drains = app_handle.state::<DrainTasks>();
+    let (task, id) = drains.start(key.clone(), || {
+        let task = tauri::async_runtime::spawn(async move {
+            kubernetes::drain::drain_node(client, &node_name, options.unwrap_or_default(), move |progress| {
+                if let Err(e) = on_progress.send(progress.clone()) {
+                    log::warn!("Failed to send drain progress: {}", e);
+                }
+            })
+            .await
+        });
+        let abort = task.inner().abort_handle();
+        (task, abort)
+    })?;
+    let result = task.await;
+    drains.remove(&key, id);
+
+    match result {
+        Ok(summary) => summary,
+        Err(tauri::Error::JoinError(e)) if e.is_cancelled() => Err(AppError::Cancelled {
+            message: "Drain cancelled; the node stays cordoned".to_string(),
+        }),
+        Err(tauri::Error::JoinError(e)) if e.is_panic() => {
+            let panic = e.into_panic();
+            let reason = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown error".to_string());
+            Err(AppError::other(format!("Drain failed unexpectedly: {}", reason)))
+        }
+        Err(e) => Err(AppError::other(e.to_string())),
+    }
+}
+
+/// Stop a running drain; pods already evicted are not restored
+#[tauri::command]
+pub async fn cancel_drain(app_handle: tauri::AppHandle, config: ClusterContext, node_name: String) -> bool {
+    app_handle
+        .state::<DrainTasks>()
+        .cancel(&drain_key(&config, &node_name))
+}
+
+fn drain_key(config: &ClusterContext, node_name: &str) -> String {
+    format!("{}/{}", config.id, node_name)
+}
+
+/// Get workloads (controllers and their pods) for a cluster
+#[tauri::command]
+pub async fn get_workloads(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    options: Option<ListOptions>,
+) -> WorkloadsResponse {
+    let client = match get_client(&app_handle, &config).await {
+        Ok(c) => c,
+        Err(e) => return WorkloadsResponse {
             items: vec![],
-            error: Some("No kubeconfig provided".to_string()),
+            continue_token: None,
+            error: Some(e),
         },
     };
-    
-    kubernetes::workloads::get_workloads(client, &config.id).await
+
+    kubernetes::workloads::get_workloads(client, &config.id, &options.unwrap_or_default()).await
+}
+
+/// Scale a Deployment, StatefulSet or ReplicaSet
+#[tauri::command]
+pub async fn scale_workload(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    request: ScaleRequest,
+) -> Result<ScaleResult, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    kubernetes::scale::scale_workload(client, &request).await
+}
+
+/// Restart the pods of a Deployment, StatefulSet or DaemonSet with a rolling update
+#[tauri::command]
+pub async fn rollout_restart(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    kind: String,
+    namespace: String,
+    name: String,
+) -> Result<(), AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    kubernetes::rollout::restart(client, &kind, &namespace, &name).await
+}
+
+/// Follow a rollout, streaming progress until it completes or times out
+#[tauri::command]
+pub async fn rollout_status(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    kind: String,
+    namespace: String,
+    name: String,
+    timeout_seconds: Option<u64>,
+    on_progress: Channel<RolloutStatus>,
+) -> Result<RolloutStatus, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+    let timeout = timeout_seconds.map(std::time::Duration::from_secs);
+
+    kubernetes::rollout::watch_status(client, &kind, &namespace, &name, timeout, |status| {
+        if let Err(e) = on_progress.send(status.clone()) {
+            log::warn!("Failed to send rollout status: {}", e);
+        }
+    })
+    .await
+}
+
+/// List the revisions of a Deployment, StatefulSet or DaemonSet
+#[tauri::command]
+pub async fn rollout_history(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    kind: String,
+    namespace: String,
+    name: String,
+) -> Result<Vec<RolloutRevision>, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    kubernetes::rollout::history(client, &kind, &namespace, &name).await
+}
+
+/// Roll back to a revision, by default the previous one
+#[tauri::command]
+pub async fn rollout_undo(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    kind: String,
+    namespace: String,
+    name: String,
+    to_revision: Option<i64>,
+) -> Result<RolloutUndo, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    kubernetes::rollout::undo(client, &kind, &namespace, &name, to_revision).await
 }
 
 /// Get generic resources (Services, ConfigMaps, etc.)
 #[tauri::command]
-pub async fn get_resources(config: ClusterContext, resource_type: String) -> ResourcesResponse {
-    let client = match (
-        config.kubeconfig.as_ref().map(|kc| kubernetes::client::from_kubeconfig(kc)),
-        config.kubeconfig.is_none().then(|| kubernetes::client::from_default())
-    ) {
-        (Some(fut), _) => match fut.await {
-            Ok(c) => c,
-            Err(e) => return ResourcesResponse {
-                items: vec![],
-                error: Some(format!("Failed to create client: {}", e)),
-            },
-        },
-        (None, Some(fut)) => match fut.await {
-            Ok(c) => c,
-            Err(e) => return ResourcesResponse {
-                items: vec![],
-                error: Some(format!("Failed to create client: {}", e)),
-            },
-        },
-        _ => return ResourcesResponse {
+pub async fn get_resources(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    resource_type: String,
+    options: Option<ListOptions>,
+) -> ResourcesResponse {
+    let client = match get_client(&app_handle, &config).await {
+        Ok(c) => c,
+        Err(e) => return ResourcesResponse {
             items: vec![],
-            error: Some("No kubeconfig provided".to_string()),
+            continue_token: None,
+            error: Some(e),
         },
     };
-    
-    kubernetes::resources::get_resources(client, &resource_type).await
+
+    kubernetes::resources::get_resources(client, &resource_type, &options.unwrap_or_default()).await
+}
+
+/// Delete objects of any supported type, or preview the deletion with a dry run
+#[tauri::command]
+pub async fn delete_resource(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    request: DeleteRequest,
+) -> Result<Vec<DeleteResult>, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    kubernetes::delete::delete_resources(client, &request).await
+}
+
+/// Start streaming live changes to a resource type; returns the subscription id
+#[tauri::command]
+pub async fn subscribe_resources(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    resource_type: String,
+    options: Option<ListOptions>,
+    on_event: Channel<Vec<ResourceEvent>>,
+) -> Result<String, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    let watch = kubernetes::subscriptions::watch_resources(
+        client,
+        &resource_type,
+        options.unwrap_or_default(),
+        move |events| match on_event.send(events) {
+            Ok(()) => true,
+            Err(e) => {
+                log::info!("Stopping resource watch, channel closed: {}", e);
+                false
+            }
+        },
+    )?;
+
+    let task = tauri::async_runtime::spawn(watch);
+    Ok(app_handle
+        .state::<Subscriptions>()
+        .insert(task.inner().abort_handle()))
+}
+
+/// Stop a resource subscription
+#[tauri::command]
+pub async fn unsubscribe_resources(app_handle: tauri::AppHandle, subscription_id: String) -> bool {
+    app_handle.state::<Subscriptions>().remove(&subscription_id)
+}
+
+/// Run step-by-step connectivity checks against a cluster
+#[tauri::command]
+pub async fn diagnose_cluster(config: ClusterContext) -> DiagnosticReport {
+    kubernetes::diagnostics::diagnose_cluster(&config).await
 }
 
 /// Save clusters to persistent storage
@@ -85,54 +307,147 @@ pub async fn get_resources(config: ClusterContext, resource_type: String) -> Res
 pub async fn save_clusters(
     app_handle: tauri::AppHandle,
     clusters: Vec<ClusterContext>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    // Clusters missing from the saved list were removed, so drop their clients
+    app_handle.state::<ClientPool>().retain(&clusters);
+
     persistence::save_clusters(&app_handle, clusters)
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 /// Load clusters from persistent storage
 #[tauri::command]
-pub async fn load_clusters(app_handle: tauri::AppHandle) -> Result<Vec<ClusterContext>, String> {
+pub async fn load_clusters(app_handle: tauri::AppHandle) -> Result<Vec<ClusterContext>, AppError> {
     persistence::load_clusters(&app_handle)
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+/// Discover clusters from the kubeconfig files plus any extra files or directories
+#[tauri::command]
+pub async fn discover_clusters(extra_paths: Option<Vec<String>>) -> Vec<DiscoveredCluster> {
+    kubernetes::discovery::discover_clusters(&extra_paths.unwrap_or_default()).await
+}
+
+/// Probe every discovered context in parallel, streaming each result as it arrives
+#[tauri::command]
+pub async fn probe_discovered_clusters(
+    extra_paths: Option<Vec<String>>,
+    timeout_secs: Option<u64>,
+    on_result: Channel<ClusterProbe>,
+) -> Result<Vec<ClusterProbe>, AppError> {
+    kubernetes::probe::probe_clusters(
+        &extra_paths.unwrap_or_default(),
+        timeout_secs.map(Duration::from_secs),
+        |probe| {
+            if let Err(e) = on_result.send(probe.clone()) {
+                log::warn!("Failed to send cluster probe: {}", e);
+            }
+        },
+    )
+    .await
+}
+
+/// Turn a discovered context into a ready-to-save cluster with a self-contained kubeconfig
+#[tauri::command]
+pub async fn import_discovered_cluster(
+    context_name: String,
+    extra_paths: Option<Vec<String>>,
+) -> Result<ClusterContext, AppError> {
+    kubernetes::discovery::import_context(&context_name, &extra_paths.unwrap_or_default())
+        .map_err(AppError::from)
+}
+
+/// Rename a context in the kubeconfig file that defines it
+#[tauri::command]
+pub async fn rename_kubeconfig_context(
+    old_name: String,
+    new_name: String,
+    extra_paths: Option<Vec<String>>,
+) -> Result<Vec<DiscoveredCluster>, AppError> {
+    let extra_paths = extra_paths.unwrap_or_default();
+    kubernetes::kubeconfig::rename_context(&old_name, &new_name, &extra_paths)?;
+    Ok(kubernetes::discovery::discover_clusters(&extra_paths).await)
+}
+
+/// Delete a context and the cluster and user entries only it used
+#[tauri::command]
+pub async fn delete_kubeconfig_context(
+    context_name: String,
+    extra_paths: Option<Vec<String>>,
+) -> Result<Vec<DiscoveredCluster>, AppError> {
+    let extra_paths = extra_paths.unwrap_or_default();
+    kubernetes::kubeconfig::delete_context(&context_name, &extra_paths)?;
+    Ok(kubernetes::discovery::discover_clusters(&extra_paths).await)
+}
+
+/// Set the default namespace of a context (an empty namespace clears it)
+#[tauri::command]
+pub async fn set_kubeconfig_context_namespace(
+    context_name: String,
+    namespace: String,
+    extra_paths: Option<Vec<String>>,
+) -> Result<Vec<DiscoveredCluster>, AppError> {
+    let extra_paths = extra_paths.unwrap_or_default();
+    kubernetes::kubeconfig::set_context_namespace(&context_name, &namespace, &extra_paths)?;
+    Ok(kubernetes::discovery::discover_clusters(&extra_paths).await)
+}
+
+/// Switch the kubeconfig's current-context
+#[tauri::command]
+pub async fn use_kubeconfig_context(
+    context_name: String,
+    extra_paths: Option<Vec<String>>,
+) -> Result<Vec<DiscoveredCluster>, AppError> {
+    let extra_paths = extra_paths.unwrap_or_default();
+    kubernetes::kubeconfig::use_context(&context_name, &extra_paths)?;
+    Ok(kubernetes::discovery::discover_clusters(&extra_paths).await)
+}
+
+/// Restart the kubeconfig watcher so it also covers extra files or directories
+#[tauri::command]
+pub async fn watch_kubeconfigs(app_handle: tauri::AppHandle, extra_paths: Option<Vec<String>>) {
+    start_kubeconfig_watch(app_handle, extra_paths.unwrap_or_default());
+}
+
+/// Spawn the background task that emits `kubeconfig-changed` events
+pub fn start_kubeconfig_watch(app_handle: tauri::AppHandle, extra_paths: Vec<String>) {
+    let emitter = app_handle.clone();
+    let task = tauri::async_runtime::spawn(kubernetes::watcher::watch_kubeconfigs(extra_paths, move |change| {
+        // Clients built from the default kubeconfig may now hold stale credentials
+        emitter.state::<ClientPool>().clear_default_kubeconfig_clients();
+
+        if let Err(e) = emitter.emit("kubeconfig-changed", change) {
+            log::warn!("Failed to emit kubeconfig change: {}", e);
+        }
+    }));
+
+    app_handle
+        .state::<KubeconfigWatcher>()
+        .replace(task.inner().abort_handle());
 }
 
-/// Discover clusters from default kubeconfig
+/// List the contexts in a kubeconfig so one can be picked when adding a cluster
 #[tauri::command]
-pub async fn discover_clusters() -> Vec<DiscoveredCluster> {
-    kubernetes::discovery::discover_clusters().await
+pub async fn list_kubeconfig_contexts(kubeconfig: String) -> Result<Vec<DiscoveredCluster>, AppError> {
+    kubernetes::discovery::list_contexts(&kubeconfig)
+        .map_err(AppError::from)
 }
 
 /// Execute a command in a pod
 #[tauri::command]
 pub async fn exec_pod_command(
+    app_handle: tauri::AppHandle,
     config: ClusterContext,
     request: ExecRequest,
 ) -> ExecResponse {
-    let client = match (
-        config.kubeconfig.as_ref().map(|kc| kubernetes::client::from_kubeconfig(kc)),
-        config.kubeconfig.is_none().then(|| kubernetes::client::from_default())
-    ) {
-        (Some(fut), _) => match fut.await {
-            Ok(c) => c,
-            Err(e) => return ExecResponse {
-                output: String::new(),
-                error: Some(format!("Failed to create client: {}", e)),
-            },
-        },
-        (None, Some(fut)) => match fut.await {
-            Ok(c) => c,
-            Err(e) => return ExecResponse {
-                output: String::new(),
-                error: Some(format!("Failed to create client: {}", e)),
-            },
-        },
-        _ => return ExecResponse {
+    let client = match get_client(&app_handle, &config).await {
+        Ok(c) => c,
+        Err(e) => return ExecResponse {
             output: String::new(),
-            error: Some("No kubeconfig provided".to_string()),
+            error: Some(e),
         },
     };
-    
+
     println!("Tauri Command Exec: namespace='{}', pod='{}', container={:?}", request.namespace, request.pod_name, request.container);
 
     match kubernetes::exec::exec_in_pod(
@@ -148,7 +463,7 @@ pub async fn exec_pod_command(
         },
         Err(e) => ExecResponse {
             output: String::new(),
-            error: Some(e.to_string()),
+            error: Some(AppError::from(e)),
         },
     }
 }
@@ -156,21 +471,98 @@ pub async fn exec_pod_command(
 /// Get containers in a pod
 #[tauri::command]
 pub async fn get_pod_containers(
+    app_handle: tauri::AppHandle,
     config: ClusterContext,
     namespace: String,
     pod_name: String,
-) -> Result<Vec<String>, String> {
-    let client = if let Some(ref kubeconfig) = config.kubeconfig {
-        kubernetes::client::from_kubeconfig(kubeconfig)
-            .await
-            .map_err(|e| format!("Failed to create client: {}", e))?
-    } else {
-        kubernetes::client::from_default()
-            .await
-            .map_err(|e| format!("Failed to create client: {}", e))?
-    };
-    
+) -> Result<Vec<String>, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
     kubernetes::exec::get_pod_containers(client, &namespace, &pod_name)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+/// Fetch the log of a pod's container
+#[tauri::command]
+pub async fn get_pod_logs(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    namespace: String,
+    pod_name: String,
+    options: Option<LogOptions>,
+) -> Result<PodLogs, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    kubernetes::logs::get_pod_logs(client, &namespace, &pod_name, &options.unwrap_or_default()).await
+}
+
+/// Follow a pod's container log, streaming batches of lines until unsubscribed
+#[tauri::command]
+pub async fn follow_pod_logs(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    namespace: String,
+    pod_name: String,
+    options: Option<LogOptions>,
+    on_event: Channel<LogEvent>,
+) -> Result<String, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    let follow = kubernetes::logs::follow_pod_logs(
+        client,
+        &namespace,
+        &pod_name,
+        &options.unwrap_or_default(),
+        move |event| match on_event.send(event) {
+            Ok(()) => true,
+            Err(e) => {
+                log::info!("Stopping log stream, channel closed: {}", e);
+                false
+            }
+        },
+    )
+    .await?;
+
+    let task = tauri::async_runtime::spawn(follow);
+    Ok(app_handle
+        .state::<Subscriptions>()
+        .insert(task.inner().abort_handle()))
+}
+
+/// Follow the logs of every pod a selector or Deployment matches, streaming filtered line batches
+#[tauri::command]
+pub async fn tail_pod_logs(
+    app_handle: tauri::AppHandle,
+    config: ClusterContext,
+    request: LogTailRequest,
+    options: Option<LogOptions>,
+    on_event: Channel<LogEvent>,
+) -> Result<String, AppError> {
+    let client = get_client(&app_handle, &config).await?;
+
+    let tail = kubernetes::logs::tail_pod_logs(
+        client,
+        &request,
+        &options.unwrap_or_default(),
+        move |event| match on_event.send(event) {
+            Ok(()) => true,
+            Err(e) => {
+                log::info!("Stopping log tail, channel closed: {}", e);
+                false
+            }
+        },
+    )
+    .await?;
+
+    let task = tauri::async_runtime::spawn(tail);
+    Ok(app_handle
+        .state::<Subscriptions>()
+        .insert(task.inner().abort_handle()))
+}
+
+/// Stop following a log
+#[tauri::command]
+pub async fn stop_pod_logs(app_handle: tauri::AppHandle, subscription_id: String) -> bool {
+    app_handle.state::<Subscriptions>().remove(&subscription_id)
 }
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+
+/// Categorized error returned by every backend command
+///
+/// Serialized with a `kind` tag so the UI can suggest a fix for each category
+/// rather than showing the raw error chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum AppError {
+    /// The API server could not be reached (connection refused, DNS failure, no route)
+    Unreachable { message: String },
+    /// The TLS handshake failed or the server certificate was not trusted
+    Tls { message: String },
+    /// The API server rejected the credentials (HTTP 401)
+    Unauthorized { message: String },
+    /// The user is authenticated but RBAC denies the request (HTTP 403)
+    Forbidden {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        verb: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resource: Option<String>,
+    },
+    /// The requested object does not exist (HTTP 404)
+    NotFound { message: String },
+    /// The request or connection timed out
+    Timeout { message: String },
+    /// The exec auth plugin or token file could not produce credentials
+    AuthPluginFailed { message: String },
+    /// Any other error status returned by the API server
+    ApiServerError { message: String, code: u16 },
+    /// The kubeconfig could not be parsed or is missing entries
+    InvalidKubeconfig { message: String },
+    /// The request itself was invalid before reaching the cluster
+    InvalidRequest { message: String },
+    /// The operation was cancelled by the user
+    Cancelled { message: String },
+    /// Anything that doesn't fit a category above
+    Other { message: String },
+}
+
+impl AppError {
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        AppError::InvalidRequest { message: message.into() }
+    }
+
+    pub fn other(message: impl Into<String>) -> Self {
+        AppError::Other { message: message.into() }
+    }
+
+    /// Human-readable description of the error
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::Unreachable { message }
+            | AppError::Tls { message }
+            | AppError::Unauthorized { message }
+            | AppError::Forbidden { message, .. }
+            | AppError::NotFound { message }
+            | AppError::Timeout { message }
+            | AppError::AuthPluginFailed { message }
+            | AppError::ApiServerError { message, .. }
+            | AppError::InvalidKubeconfig { message }
+            | AppError::InvalidRequest { message }
+            | AppError::Cancelled { message }
+            | AppError::Other { message } => message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl StdError for AppError {}
+
+impl From<kube::Error> for AppError {
+    fn from(e: kube::Error) -> Self {
+        classify_kube(&e)
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        if let Some(app_error) = e.downcast_ref::<AppError>() {
+            return app_error.clone();
+        }
+        if let Some(kube_error) = e.downcast_ref::<kube::Error>() {
+            return classify_kube(kube_error);
+        }
+        if e.downcast_ref::<kube::config::KubeconfigError>().is_some()
+            || e.downcast_ref::<kube::config::InferConfigError>().is_some()
+        {
+            return AppError::InvalidKubeconfig { message: format_chain(e.as_ref()) };
+        }
+        if let Some(io_error) = e.downcast_ref::<io::Error>() {
+            if let Some(classified) = classify_io(io_error, format_chain(e.as_ref())) {
+                return classified;
+            }
+        }
+        AppError::other(format_chain(e.as_ref()))
+    }
+}
+
+/// Map a kube client error onto a category
+fn classify_kube(e: &kube::Error) -> AppError {
+    let message = format_chain(e);
+
+    match e {
+        kube::Error::Api(response) => match response.code {
+            401 => AppError::Unauthorized { message: response.message.clone() },
+            403 => {
+                let (verb, resource) = parse_forbidden(&response.message);
+                AppError::Forbidden {
+                    message: response.message.clone(),
+                    verb,
+                    resource,
+                }
+            }
+            404 => AppError::NotFound { message: response.message.clone() },
+            408 | 504 => AppError::Timeout { message: response.message.clone() },
+            code => AppError::ApiServerError {
+                message: response.message.clone(),
+                code,
+            },
+        },
+        kube::Error::Auth(_) => AppError::AuthPluginFailed { message },
+        kube::Error::InferConfig(_) => AppError::InvalidKubeconfig { message },
+        kube::Error::RustlsTls(_) | kube::Error::TlsRequired => AppError::Tls { message },
+        kube::Error::HyperError(_) | kube::Error::Service(_) | kube::Error::ReadEvents(_) => {
+            find_io_error(e)
+                .and_then(|io_error| classify_io(io_error, message.clone()))
+                .unwrap_or(AppError::Unreachable { message })
+        }
+        _ => AppError::other(message),
+    }
+}
+
+/// Map a transport-level I/O error onto a category
+fn classify_io(e: &io::Error, message: String) -> Option<AppError> {
+    match e.kind() {
+        io::ErrorKind::TimedOut => Some(AppError::Timeout { message }),
+        // rustls reports handshake and certificate failures as invalid data
+        io::ErrorKind::InvalidData => Some(AppError::Tls { message }),
+        io::ErrorKind::ConnectionRefused
+        | io::ErrorKind::ConnectionReset
+        | io::ErrorKind::ConnectionAborted
+        | io::ErrorKind::NotConnected
+        | io::ErrorKind::AddrNotAvailable
+        | io::ErrorKind::BrokenPipe
+        | io::ErrorKind::UnexpectedEof => Some(AppError::Unreachable { message }),
+        _ => None,
+    }
+}
+
+/// Find the innermost I/O error in an error's source chain
+fn find_io_error<'a>(e: &'a (dyn StdError + 'static)) -> Option<&'a io::Error> {
+    let mut found = None;
+    let mut current: Option<&(dyn StdError + 'static)> = Some(e);
+    while let Some(err) = current {
+        if let Some(io_error) = err.downcast_ref::<io::Error>() {
+            found = Some(io_error);
+        }
+        current = err.source();
+    }
+    found
+}
+
+/// Join an error and its sources into one line
+fn format_chain(e: &(dyn StdError + 'static)) -> String {
+    let mut message = e.to_string();
+    let mut current = e.source();
+    while let Some(err) = current {
+        let text = err.to_string();
+        if !message.contains(&text) {
+            message.push_str(": ");
+            message.push_str(&text);
+        }
+        current = err.source();
+    }
+    message
+}
+
+/// Pull the verb and resource out of an RBAC denial such as
+/// `pods is forbidden: User "jane" cannot list resource "pods" in API group "" at the cluster scope`
+fn parse_forbidden(message: &str) -> (Option<String>, Option<String>) {
+    let Some((_, rest)) = message.split_once(" cannot ") else {
+        return (None, None);
+    };
+
+    let verb = rest.split_whitespace().next().map(str::to_string);
+    let resource = rest
+        .split_once("resource \"")
+        .and_then(|(_, tail)| tail.split_once('"'))
+        .map(|(resource, _)| resource.to_string());
+
+    (verb, resource)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rbac_denials() {
+        let cases = [
+            (
+                r#"pods is forbidden: User "jane" cannot list resource "pods" in API group "" at the cluster scope"#,
+                (Some("list"), Some("pods")),
+            ),
+            (
+                r#"deployments.apps "web" is forbidden: User "system:serviceaccount:ci:deployer" cannot patch resource "deployments/scale" in API group "apps" in the namespace "prod""#,
+                (Some("patch"), Some("deployments/scale")),
+            ),
+            (
+                r#"User "jane" cannot get path "/metrics""#,
+                (Some("get"), None),
+            ),
+            ("pods is forbidden", (None, None)),
+            ("", (None, None)),
+        ];
+
+        for (message, (verb, resource)) in cases {
+            assert_eq!(
+                parse_forbidden(message),
+                (verb.map(str::to_string), resource.map(str::to_string)),
+                "{}",
+                message
+            );
+        }
+    }
+}
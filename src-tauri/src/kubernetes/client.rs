@@ -1,19 +1,52 @@
 use anyhow::Result;
 use kube::{config::KubeConfigOptions, Config};
 
-/// Creates a Kubernetes client from a kubeconfig string
-pub async fn from_kubeconfig(kubeconfig: &str) -> Result<kube::Client> {
+use crate::types::ClusterContext;
+
+/// Loads client configuration from a kubeconfig string
+pub async fn config_from_kubeconfig(kubeconfig: &str, options: &KubeConfigOptions) -> Result<Config> {
     let config = Config::from_custom_kubeconfig(
         kube::config::Kubeconfig::from_yaml(kubeconfig)?,
-        &KubeConfigOptions::default(),
+        options,
     )
     .await?;
-    
-    Ok(kube::Client::try_from(config)?)
+
+    Ok(config)
+}
+
+/// Loads client configuration from the default kubeconfig
+pub async fn default_config(options: &KubeConfigOptions) -> Result<Config> {
+    // Only bypass inference (and with it in-cluster config) when a specific entry was asked for
+    let config = if options.context.is_none() && options.cluster.is_none() && options.user.is_none() {
+        Config::infer().await?
+    } else {
+        Config::from_kubeconfig(options).await?
+    };
+    Ok(config)
 }
 
-/// Creates a Kubernetes client from the default kubeconfig
-pub async fn from_default() -> Result<kube::Client> {
-    let config = Config::infer().await?;
+/// Loads client configuration for a saved cluster, falling back to the
+/// default kubeconfig when the cluster has none of its own
+pub async fn config_from_cluster_context(config: &ClusterContext) -> Result<Config> {
+    let options = kubeconfig_options(config);
+
+    match config.kubeconfig {
+        Some(ref kubeconfig) => config_from_kubeconfig(kubeconfig, &options).await,
+        None => default_config(&options).await,
+    }
+}
+
+/// Creates a Kubernetes client for a saved cluster
+pub async fn from_cluster_context(config: &ClusterContext) -> Result<kube::Client> {
+    let config = config_from_cluster_context(config).await?;
     Ok(kube::Client::try_from(config)?)
 }
+
+/// Context, cluster and user selection for a saved cluster
+pub fn kubeconfig_options(config: &ClusterContext) -> KubeConfigOptions {
+    KubeConfigOptions {
+        context: config.context_name.clone(),
+        cluster: config.cluster_name.clone(),
+        user: config.user_name.clone(),
+    }
+}
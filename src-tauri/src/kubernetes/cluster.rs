@@ -1,68 +1,354 @@
 use anyhow::Result;
-use k8s_openapi::api::core::v1::Node;
-use kube::api::ListParams;
+use k8s_openapi::api::core::v1::{Node, Pod, ResourceRequirements};
+use kube::api::{ApiResource, DynamicObject, GroupVersionKind, ListParams};
 use kube::Api;
+use std::collections::HashSet;
 
-use crate::types::ClusterInfo;
+use crate::error::AppError;
+use crate::kubernetes::{platform, quantity};
+use crate::types::{ClusterInfo, PlatformInfo, UsageSource};
 
 /// Get cluster information (node count, status, etc.)
 pub async fn get_info(client: kube::Client) -> Result<ClusterInfo> {
-    let nodes: Api<Node> = Api::all(client);
+    let nodes: Api<Node> = Api::all(client.clone());
     
     match nodes.list(&ListParams::default()).await {
         Ok(node_list) => {
             let node_count = node_list.items.len();
-            
-            // Placeholder values for CPU and memory usage
-            // In a real implementation, you'd query metrics-server or similar
-            let cpu_usage = (rand::random::<f64>() * 20.0 + 10.0).floor();
-            let mem_usage = (rand::random::<f64>() * 30.0 + 20.0).floor();
+            let (usage, health, version) = tokio::join!(
+                get_usage(client.clone(), &node_list.items),
+                assess_health(client.clone(), &node_list.items),
+                client.apiserver_version(),
+            );
+            if let Err(e) = &version {
+                log::info!("Could not read /version: {}", e);
+            }
             
             Ok(ClusterInfo {
-                status: "Healthy".to_string(),
+                status: health.level.as_str().to_string(),
+                status_reasons: health.reasons,
                 node_count,
-                cpu_usage,
-                mem_usage,
+                cpu_usage: usage.cpu,
+                mem_usage: usage.memory,
+                usage_source: usage.source,
+                platform: platform::describe(version.ok().as_ref(), &node_list.items),
                 error: None,
             })
         }
-        Err(e) => {
-            let error_msg = if e.to_string().contains("connection refused") {
-                "Connection refused. Is the cluster running?".to_string()
-            } else {
-                e.to_string()
-            };
-            
-            Ok(ClusterInfo {
-                status: "Error".to_string(),
-                node_count: 0,
-                cpu_usage: 0.0,
-                mem_usage: 0.0,
-                error: Some(error_msg),
-            })
+        Err(e) => Ok(ClusterInfo {
+            status: "Error".to_string(),
+            status_reasons: vec![],
+            node_count: 0,
+            cpu_usage: 0.0,
+            mem_usage: 0.0,
+            usage_source: UsageSource::Unavailable,
+            platform: PlatformInfo::default(),
+            error: Some(AppError::from(e)),
+        }),
+    }
+}
+
+/// Check `/readyz?verbose` and return the names of the failing checks
+pub async fn readyz_failures(client: &kube::Client) -> Result<Vec<String>, kube::Error> {
+    let request = http::Request::get("/readyz?verbose")
+        .body(vec![])
+        .map_err(kube::Error::HttpError)?;
+
+    // A failing readyz answers 500 with the verbose check list as the error body
+    let body = match client.request_text(request).await {
+        Ok(body) => body,
+        Err(kube::Error::Api(response)) if response.code == 500 => response.message,
+        Err(e) => return Err(e),
+    };
+
+    Ok(body
+        .split('\n')
+        .flat_map(|line| line.split("\\n"))
+        .filter_map(|line| line.trim_matches('"').strip_prefix("[-]"))
+        .map(|check| check.split_whitespace().next().unwrap_or(check).to_string())
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum HealthLevel {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl HealthLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HealthLevel::Healthy => "Healthy",
+            HealthLevel::Warning => "Warning",
+            HealthLevel::Critical => "Critical",
         }
     }
 }
 
-// Simple random function placeholder
-mod rand {
-    pub fn random<T: Random>() -> T {
-        T::random()
+/// Overall health with the reasons that lowered it
+struct Health {
+    level: HealthLevel,
+    reasons: Vec<String>,
+}
+
+impl Health {
+    fn report(&mut self, level: HealthLevel, reason: String) {
+        self.level = self.level.max(level);
+        self.reasons.push(reason);
     }
-    
-    pub trait Random {
-        fn random() -> Self;
+}
+
+/// Derive cluster health from node conditions, API server readiness and kube-system pods
+async fn assess_health(client: kube::Client, nodes: &[Node]) -> Health {
+    let mut health = Health {
+        level: HealthLevel::Healthy,
+        reasons: vec![],
+    };
+
+    check_nodes(&mut health, nodes);
+
+    match readyz_failures(&client).await {
+        Ok(failures) if failures.is_empty() => {}
+        Ok(failures) => health.report(
+            HealthLevel::Critical,
+            format!("API server readiness checks failing: {}", failures.join(", ")),
+        ),
+        // Some RBAC setups hide /readyz; that alone says nothing about health
+        Err(e) => log::info!("Could not read /readyz: {}", e),
     }
-    
-    impl Random for f64 {
-        fn random() -> Self {
-            // Simple pseudo-random using system time
-            use std::time::{SystemTime, UNIX_EPOCH};
-            let nanos = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .subsec_nanos();
-            (nanos % 1000) as f64 / 1000.0
+
+    let system_pods: Api<Pod> = Api::namespaced(client, "kube-system");
+    match system_pods.list(&ListParams::default()).await {
+        Ok(list) => check_system_pods(&mut health, &list.items),
+        Err(e) => log::info!("Could not list kube-system pods: {}", e),
+    }
+
+    health
+}
+
+/// Report nodes that aren't Ready or are under resource pressure
+fn check_nodes(health: &mut Health, nodes: &[Node]) {
+    if nodes.is_empty() {
+        health.report(HealthLevel::Critical, "Cluster has no nodes".to_string());
+        return;
+    }
+
+    let names_where = |predicate: &dyn Fn(&Node) -> bool| {
+        nodes
+            .iter()
+            .filter(|n| predicate(n))
+            .map(|n| n.metadata.name.clone().unwrap_or_default())
+            .collect::<Vec<_>>()
+    };
+
+    let not_ready = names_where(&|n| node_condition(n, "Ready") != Some("True"));
+    if !not_ready.is_empty() {
+        let level = if not_ready.len() * 2 >= nodes.len() {
+            HealthLevel::Critical
+        } else {
+            HealthLevel::Warning
+        };
+        health.report(
+            level,
+            format!("{} of {} nodes not Ready: {}", not_ready.len(), nodes.len(), not_ready.join(", ")),
+        );
+    }
+
+    for (kind, label) in [
+        ("MemoryPressure", "memory pressure"),
+        ("DiskPressure", "disk pressure"),
+        ("PIDPressure", "PID pressure"),
+        ("NetworkUnavailable", "network unavailable"),
+    ] {
+        let affected = names_where(&|n| node_condition(n, kind) == Some("True"));
+        if !affected.is_empty() {
+            health.report(HealthLevel::Warning, format!("Nodes with {}: {}", label, affected.join(", ")));
+        }
+    }
+}
+
+/// Status of a node condition such as `Ready` or `MemoryPressure`
+fn node_condition<'a>(node: &'a Node, kind: &str) -> Option<&'a str> {
+    node.status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .and_then(|conditions| conditions.iter().find(|c| c.type_ == kind))
+        .map(|c| c.status.as_str())
+}
+
+/// Report unready system pods; half or more unready means the control plane is in trouble
+///
+/// Finished pods, such as completed jobs or evicted pods left behind, are ignored.
+fn check_system_pods(health: &mut Health, pods: &[Pod]) {
+    let active: Vec<&Pod> = pods
+        .iter()
+        .filter(|p| {
+            let phase = p.status.as_ref().and_then(|s| s.phase.as_deref());
+            !matches!(phase, Some("Succeeded") | Some("Failed"))
+        })
+        .collect();
+
+    let unready: Vec<String> = active
+        .iter()
+        .filter(|p| {
+            let ready = p
+                .status
+                .as_ref()
+                .and_then(|s| s.conditions.as_ref())
+                .and_then(|conditions| conditions.iter().find(|c| c.type_ == "Ready"))
+                .map(|c| c.status.as_str());
+            ready != Some("True")
+        })
+        .map(|p| p.metadata.name.clone().unwrap_or_default())
+        .collect();
+
+    if unready.is_empty() {
+        return;
+    }
+
+    let level = if unready.len() * 2 >= active.len() {
+        HealthLevel::Critical
+    } else {
+        HealthLevel::Warning
+    };
+    health.report(
+        level,
+        format!(
+            "{} of {} kube-system pods not ready: {}",
+            unready.len(),
+            active.len(),
+            unready.join(", ")
+        ),
+    );
+}
+
+/// CPU and memory usage as a percentage of node allocatable
+struct Usage {
+    cpu: f64,
+    memory: f64,
+    source: UsageSource,
+}
+
+/// Measure usage from metrics-server, falling back to scheduled pod requests
+async fn get_usage(client: kube::Client, nodes: &[Node]) -> Usage {
+    match metrics_usage(client.clone()).await {
+        Ok((cpu, memory, measured_nodes)) => {
+            // Only compare against nodes metrics-server has reported on yet
+            let measured: Vec<Node> = nodes
+                .iter()
+                .filter(|n| n.metadata.name.as_ref().is_some_and(|name| measured_nodes.contains(name)))
+                .cloned()
+                .collect();
+            let (cpu_allocatable, memory_allocatable) = allocatable(&measured);
+
+            return Usage {
+                cpu: percent(cpu, cpu_allocatable),
+                memory: percent(memory, memory_allocatable),
+                source: UsageSource::Metrics,
+            };
+        }
+        Err(e) => log::info!("metrics.k8s.io unavailable, using pod requests: {}", e),
+    }
+
+    match requested_usage(client).await {
+        Ok((cpu, memory)) => {
+            let (cpu_allocatable, memory_allocatable) = allocatable(nodes);
+            Usage {
+                cpu: percent(cpu, cpu_allocatable),
+                memory: percent(memory, memory_allocatable),
+                source: UsageSource::Requests,
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to sum pod requests: {}", e);
+            Usage {
+                cpu: 0.0,
+                memory: 0.0,
+                source: UsageSource::Unavailable,
+            }
+        }
+    }
+}
+
+/// Sum current CPU cores and memory bytes reported by metrics-server, with the nodes covered
+async fn metrics_usage(client: kube::Client) -> Result<(f64, f64, HashSet<String>)> {
+    let gvk = GroupVersionKind::gvk("metrics.k8s.io", "v1beta1", "NodeMetrics");
+    let resource = ApiResource::from_gvk_with_plural(&gvk, "nodes");
+    let metrics: Api<DynamicObject> = Api::all_with(client, &resource);
+
+    let list = metrics.list(&ListParams::default()).await?;
+
+    let mut cpu = 0.0;
+    let mut memory = 0.0;
+    let mut measured_nodes = HashSet::new();
+    for item in &list.items {
+        let usage = &item.data["usage"];
+        cpu += usage["cpu"].as_str().and_then(quantity::parse_str).unwrap_or_default();
+        memory += usage["memory"].as_str().and_then(quantity::parse_str).unwrap_or_default();
+        if let Some(name) = &item.metadata.name {
+            measured_nodes.insert(name.clone());
+        }
+    }
+
+    Ok((cpu, memory, measured_nodes))
+}
+
+/// Sum CPU cores and memory bytes requested by pods that are scheduled and not finished
+async fn requested_usage(client: kube::Client) -> Result<(f64, f64)> {
+    let pods: Api<Pod> = Api::all(client);
+    let params = ListParams::default().fields("status.phase!=Succeeded,status.phase!=Failed");
+
+    let mut cpu = 0.0;
+    let mut memory = 0.0;
+    for pod in pods.list(&params).await?.items {
+        let Some(spec) = pod.spec else { continue };
+        if spec.node_name.is_none() {
+            continue;
         }
+
+        // Init containers run one at a time, so only the largest counts against the node
+        let sum_containers = spec.containers.iter().fold((0.0, 0.0), |acc, c| {
+            let (c_cpu, c_memory) = requests(c.resources.as_ref());
+            (acc.0 + c_cpu, acc.1 + c_memory)
+        });
+        let max_init = spec.init_containers.iter().flatten().fold((0.0f64, 0.0f64), |acc, c| {
+            let (c_cpu, c_memory) = requests(c.resources.as_ref());
+            (acc.0.max(c_cpu), acc.1.max(c_memory))
+        });
+        let overhead = spec.overhead.as_ref();
+
+        cpu += sum_containers.0.max(max_init.0)
+            + overhead.and_then(|o| o.get("cpu")).and_then(quantity::parse).unwrap_or_default();
+        memory += sum_containers.1.max(max_init.1)
+            + overhead.and_then(|o| o.get("memory")).and_then(quantity::parse).unwrap_or_default();
+    }
+
+    Ok((cpu, memory))
+}
+
+/// CPU cores and memory bytes requested by one container
+fn requests(resources: Option<&ResourceRequirements>) -> (f64, f64) {
+    let requests = resources.and_then(|r| r.requests.as_ref());
+    let get = |name: &str| requests.and_then(|r| r.get(name)).and_then(quantity::parse).unwrap_or_default();
+    (get("cpu"), get("memory"))
+}
+
+/// Sum allocatable CPU cores and memory bytes across nodes
+fn allocatable(nodes: &[Node]) -> (f64, f64) {
+    nodes
+        .iter()
+        .filter_map(|n| n.status.as_ref()?.allocatable.as_ref())
+        .fold((0.0, 0.0), |acc, alloc| {
+            let get = |name: &str| alloc.get(name).and_then(quantity::parse).unwrap_or_default();
+            (acc.0 + get("cpu"), acc.1 + get("memory"))
+        })
+}
+
+/// Percentage rounded to one decimal place
+fn percent(used: f64, total: f64) -> f64 {
+    if total <= 0.0 {
+        return 0.0;
     }
+    (used / total * 1000.0).round() / 10.0
 }
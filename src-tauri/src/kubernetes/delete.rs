@@ -0,0 +1,200 @@
+use k8s_openapi::api::apps::v1::{ControllerRevision, DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Node, PersistentVolumeClaim, Pod, Secret, Service, ServiceAccount};
+use k8s_openapi::api::networking::v1::Ingress;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::NamespaceResourceScope;
+use kube::api::{DeleteParams, ListParams, PropagationPolicy};
+use kube::Api;
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use crate::error::AppError;
+use crate::types::{DeleteDependent, DeletePropagation, DeleteRequest, DeleteResult, DeleteStatus};
+
+/// Delete objects of any type `get_resources` lists, reporting the outcome per object
+///
+/// A failure on one object doesn't stop the others. With `dry_run` nothing is
+/// deleted; instead each result lists the dependents the deletion would
+/// cascade to, following owner references (or, for a Namespace, everything
+/// in it).
+pub async fn delete_resources(client: kube::Client, request: &DeleteRequest) -> Result<Vec<DeleteResult>, AppError> {
+    if request.force && request.resource_type != "Pods" {
+        return Err(AppError::invalid_request("Only Pods can be force deleted"));
+    }
+
+    match request.resource_type.as_str() {
+        "Pods" => delete_namespaced::<Pod>(client, request).await,
+        "Nodes" => delete_all(Api::<Node>::all(client.clone()), &client, request).await,
+        "Services" => delete_namespaced::<Service>(client, request).await,
+        "ConfigMaps" => delete_namespaced::<ConfigMap>(client, request).await,
+        "Namespaces" => delete_all(Api::<Namespace>::all(client.clone()), &client, request).await,
+        "Deployments" => delete_namespaced::<Deployment>(client, request).await,
+        "StatefulSets" => delete_namespaced::<StatefulSet>(client, request).await,
+        "DaemonSets" => delete_namespaced::<DaemonSet>(client, request).await,
+        "Ingresses" => delete_namespaced::<Ingress>(client, request).await,
+        "Secrets" => delete_namespaced::<Secret>(client, request).await,
+        "PVCs" => delete_namespaced::<PersistentVolumeClaim>(client, request).await,
+        "ServiceAccounts" => delete_namespaced::<ServiceAccount>(client, request).await,
+        other => Err(AppError::invalid_request(format!("Unsupported resource type: {}", other))),
+    }
+}
+
+async fn delete_namespaced<K>(client: kube::Client, request: &DeleteRequest) -> Result<Vec<DeleteResult>, AppError>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope> + Clone + DeserializeOwned + Debug,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let namespace = request
+        .namespace
+        .as_deref()
+        .filter(|ns| !ns.is_empty())
+        .ok_or_else(|| AppError::invalid_request(format!("Deleting {} needs a namespace", request.resource_type)))?;
+
+    delete_all(Api::<K>::namespaced(client.clone(), namespace), &client, request).await
+}
+
+async fn delete_all<K>(api: Api<K>, client: &kube::Client, request: &DeleteRequest) -> Result<Vec<DeleteResult>, AppError>
+where
+    K: kube::Resource + Clone + DeserializeOwned + Debug,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let params = delete_params(request);
+    let mut results = Vec::with_capacity(request.names.len());
+
+    for name in &request.names {
+        let mut result = DeleteResult {
+            name: name.clone(),
+            namespace: request.namespace.clone(),
+            status: DeleteStatus::Failed,
+            dependents: vec![],
+            error: None,
+        };
+
+        match api.delete(name, &params).await.map(|outcome| outcome.left()) {
+            // The object is returned while it still exists: finalizers, a
+            // grace period or foreground deletion are holding it
+            Ok(Some(object)) => {
+                result.status = if request.dry_run {
+                    DeleteStatus::DryRun
+                } else if object.meta().deletion_timestamp.is_some() {
+                    DeleteStatus::Terminating
+                } else {
+                    DeleteStatus::Deleted
+                };
+                if request.dry_run && request.propagation_policy != Some(DeletePropagation::Orphan) {
+                    match dependents::<K>(client, object.meta()).await {
+                        Ok(dependents) => result.dependents = dependents,
+                        Err(e) => result.error = Some(e),
+                    }
+                }
+            }
+            Ok(None) => {
+                result.status = if request.dry_run {
+                    DeleteStatus::DryRun
+                } else {
+                    DeleteStatus::Deleted
+                };
+            }
+            Err(e) => result.error = Some(e.into()),
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+fn delete_params(request: &DeleteRequest) -> DeleteParams {
+    DeleteParams {
+        dry_run: request.dry_run,
+        // Force deletion skips the kubelet's graceful shutdown, like `kubectl delete --force`
+        grace_period_seconds: if request.force { Some(0) } else { request.grace_period_seconds },
+        propagation_policy: request.propagation_policy.map(|policy| match policy {
+            DeletePropagation::Foreground => PropagationPolicy::Foreground,
+            DeletePropagation::Background => PropagationPolicy::Background,
+            DeletePropagation::Orphan => PropagationPolicy::Orphan,
+        }),
+        ..Default::default()
+    }
+}
+
+/// Objects the garbage collector would delete along with an object
+async fn dependents<K>(client: &kube::Client, owner: &ObjectMeta) -> Result<Vec<DeleteDependent>, AppError>
+where
+    K: kube::Resource,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    if K::kind(&Default::default()) == "Namespace" {
+        return namespace_contents(client, owner.name.as_deref().unwrap_or_default()).await;
+    }
+    let (Some(namespace), Some(uid)) = (owner.namespace.as_deref(), owner.uid.clone()) else {
+        return Ok(vec![]);
+    };
+
+    // Kinds that controllers create on behalf of their owners
+    let (replica_sets, pods, revisions, jobs) = tokio::try_join!(
+        objects::<ReplicaSet>(client, namespace),
+        objects::<Pod>(client, namespace),
+        objects::<ControllerRevision>(client, namespace),
+        objects::<Job>(client, namespace),
+    )?;
+    let candidates: Vec<(DeleteDependent, ObjectMeta)> =
+        replica_sets.into_iter().chain(pods).chain(revisions).chain(jobs).collect();
+
+    // Walk down the ownership tree, e.g. Deployment -> ReplicaSet -> Pod
+    let mut dependents = vec![];
+    let mut owners = VecDeque::from([uid]);
+    while let Some(owner_uid) = owners.pop_front() {
+        for (dependent, meta) in &candidates {
+            let owned = meta.owner_references.iter().flatten().any(|o| o.uid == owner_uid);
+            if owned {
+                dependents.push(dependent.clone());
+                owners.extend(meta.uid.clone());
+            }
+        }
+    }
+    Ok(dependents)
+}
+
+/// Everything of the supported types in a namespace
+async fn namespace_contents(client: &kube::Client, namespace: &str) -> Result<Vec<DeleteDependent>, AppError> {
+    let mut contents = vec![];
+    contents.extend(objects::<Deployment>(client, namespace).await?);
+    contents.extend(objects::<StatefulSet>(client, namespace).await?);
+    contents.extend(objects::<DaemonSet>(client, namespace).await?);
+    contents.extend(objects::<ReplicaSet>(client, namespace).await?);
+    contents.extend(objects::<Job>(client, namespace).await?);
+    contents.extend(objects::<Pod>(client, namespace).await?);
+    contents.extend(objects::<Service>(client, namespace).await?);
+    contents.extend(objects::<Ingress>(client, namespace).await?);
+    contents.extend(objects::<ConfigMap>(client, namespace).await?);
+    contents.extend(objects::<Secret>(client, namespace).await?);
+    contents.extend(objects::<PersistentVolumeClaim>(client, namespace).await?);
+    contents.extend(objects::<ServiceAccount>(client, namespace).await?);
+    Ok(contents.into_iter().map(|(dependent, _)| dependent).collect())
+}
+
+async fn objects<K>(client: &kube::Client, namespace: &str) -> Result<Vec<(DeleteDependent, ObjectMeta)>, kube::Error>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope> + Clone + DeserializeOwned + Debug,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let list = Api::<K>::namespaced(client.clone(), namespace)
+        .list(&ListParams::default())
+        .await?;
+    let kind = K::kind(&Default::default()).to_string();
+
+    Ok(list
+        .items
+        .into_iter()
+        .map(|item| {
+            let meta = item.meta().clone();
+            let dependent = DeleteDependent {
+                kind: kind.clone(),
+                name: meta.name.clone().unwrap_or_default(),
+                namespace: meta.namespace.clone(),
+            };
+            (dependent, meta)
+        })
+        .collect())
+}
@@ -0,0 +1,294 @@
+use k8s_openapi::api::authentication::v1::SelfSubjectReview;
+use k8s_openapi::api::authorization::v1::{ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec};
+use kube::api::PostParams;
+use kube::client::ConfigExt;
+use kube::{Api, Config};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::TlsConnector;
+
+use crate::error::AppError;
+use crate::kubernetes::{client, cluster};
+use crate::types::{ClusterContext, DiagnosticReport, DiagnosticStep, DiagnosticStatus};
+
+/// How long any single check may take before it is reported as a timeout
+const STEP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run ordered connectivity checks against a cluster, stopping at the first failure
+pub async fn diagnose_cluster(config: &ClusterContext) -> DiagnosticReport {
+    let started = Instant::now();
+    let mut run = Diagnosis::default();
+
+    let kube_config = run
+        .step("kubeconfig", "Parse kubeconfig", async {
+            let kube_config = client::config_from_cluster_context(config).await?;
+            let detail = format!("Server {}", kube_config.cluster_url);
+            Ok((kube_config, detail))
+        })
+        .await;
+
+    let server = kube_config.as_ref().map(|c| c.cluster_url.to_string());
+
+    let addrs = run
+        .step("dns", "Resolve server address", async {
+            let (host, port) = host_and_port(kube_config.as_ref().unwrap())?;
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port))
+                .await
+                .map_err(|e| AppError::Unreachable {
+                    message: format!("Failed to resolve {}: {}", host, e),
+                })?
+                .collect();
+            let detail = addrs.iter().map(|a| a.ip().to_string()).collect::<Vec<_>>().join(", ");
+            Ok((addrs, format!("{} resolved to {}", host, detail)))
+        })
+        .await;
+
+    let stream = run
+        .step("tcp", "Open TCP connection", async {
+            let mut last_error = None;
+            for addr in addrs.as_deref().unwrap_or_default() {
+                match TcpStream::connect(addr).await {
+                    Ok(stream) => return Ok((stream, format!("Connected to {}", addr))),
+                    Err(e) => last_error = Some(e),
+                }
+            }
+            Err(AppError::Unreachable {
+                message: last_error
+                    .map(|e| e.to_string())
+                    .unwrap_or_else(|| "No addresses to connect to".to_string()),
+            })
+        })
+        .await;
+
+    if kube_config.as_ref().is_some_and(|c| c.cluster_url.scheme_str() != Some("https")) {
+        run.skip("tls", "TLS handshake", "Server uses plain HTTP");
+    } else {
+        run.step("tls", "TLS handshake", async {
+            let kube_config = kube_config.as_ref().unwrap();
+            let stream = stream.unwrap();
+            tls_handshake(kube_config, stream)
+                .await
+                .map_err(|message| AppError::Tls { message })
+                .map(|detail| ((), detail))
+        })
+        .await;
+    }
+
+    let kube_client = run
+        .step("auth", "Authenticate", async {
+            let kube_config = kube_config.clone().unwrap();
+            let method = auth_method(&kube_config);
+            let kube_client = kube::Client::try_from(kube_config)?;
+
+            // SelfSubjectReview runs the auth plugin and reports who the server thinks we are
+            let reviews: Api<SelfSubjectReview> = Api::all(kube_client.clone());
+            let detail = match reviews.create(&PostParams::default(), &SelfSubjectReview::default()).await {
+                Ok(review) => {
+                    let username = review
+                        .status
+                        .and_then(|s| s.user_info)
+                        .and_then(|u| u.username)
+                        .unwrap_or_else(|| "unknown user".to_string());
+                    format!("Authenticated as {} via {}", username, method)
+                }
+                // Servers older than 1.28 don't serve SelfSubjectReview
+                Err(kube::Error::Api(response)) if response.code == 404 => {
+                    format!("Credentials loaded via {}", method)
+                }
+                Err(e) => return Err(e.into()),
+            };
+            Ok((kube_client, detail))
+        })
+        .await;
+
+    run.step("version", "Query /version", async {
+        let info = kube_client.clone().unwrap().apiserver_version().await?;
+        Ok(((), format!("{} ({})", info.git_version, info.platform)))
+    })
+    .await;
+
+    run.step("readyz", "Check /readyz", async {
+        let failures = cluster::readyz_failures(kube_client.as_ref().unwrap()).await?;
+        if failures.is_empty() {
+            Ok(((), "API server is ready".to_string()))
+        } else {
+            Err(AppError::ApiServerError {
+                message: format!("Failing checks: {}", failures.join(", ")),
+                code: 500,
+            })
+        }
+    })
+    .await;
+
+    run.step("rbac", "Check permission to list pods", async {
+        let reviews: Api<SelfSubjectAccessReview> = Api::all(kube_client.clone().unwrap());
+        let review = SelfSubjectAccessReview {
+            spec: SelfSubjectAccessReviewSpec {
+                resource_attributes: Some(ResourceAttributes {
+                    verb: Some("list".to_string()),
+                    resource: Some("pods".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let status = reviews
+            .create(&PostParams::default(), &review)
+            .await?
+            .status
+            .unwrap_or_default();
+
+        if status.allowed {
+            Ok(((), "Allowed to list pods in all namespaces".to_string()))
+        } else {
+            Err(AppError::Forbidden {
+                message: status
+                    .reason
+                    .unwrap_or_else(|| "Not allowed to list pods in all namespaces".to_string()),
+                verb: Some("list".to_string()),
+                resource: Some("pods".to_string()),
+            })
+        }
+    })
+    .await;
+
+    DiagnosticReport {
+        server,
+        failed_step: run.failed_step,
+        steps: run.steps,
+        total_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Accumulates step results; once a step fails every later step is skipped
+#[derive(Default)]
+struct Diagnosis {
+    steps: Vec<DiagnosticStep>,
+    failed_step: Option<String>,
+}
+
+impl Diagnosis {
+    async fn step<T, F>(&mut self, id: &str, label: &str, check: F) -> Option<T>
+    where
+        F: Future<Output = Result<(T, String), AppError>>,
+    {
+        if self.failed_step.is_some() {
+            self.skip(id, label, "Skipped after an earlier failure");
+            return None;
+        }
+
+        let started = Instant::now();
+        let result = tokio::time::timeout(STEP_TIMEOUT, check)
+            .await
+            .unwrap_or_else(|_| {
+                Err(AppError::Timeout {
+                    message: format!("No response within {}s", STEP_TIMEOUT.as_secs()),
+                })
+            });
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        match result {
+            Ok((value, detail)) => {
+                self.push(id, label, DiagnosticStatus::Passed, duration_ms, Some(detail), None);
+                Some(value)
+            }
+            Err(error) => {
+                self.failed_step = Some(id.to_string());
+                self.push(id, label, DiagnosticStatus::Failed, duration_ms, None, Some(error));
+                None
+            }
+        }
+    }
+
+    fn skip(&mut self, id: &str, label: &str, detail: &str) {
+        self.push(id, label, DiagnosticStatus::Skipped, 0, Some(detail.to_string()), None);
+    }
+
+    fn push(
+        &mut self,
+        id: &str,
+        label: &str,
+        status: DiagnosticStatus,
+        duration_ms: u64,
+        detail: Option<String>,
+        error: Option<AppError>,
+    ) {
+        self.steps.push(DiagnosticStep {
+            id: id.to_string(),
+            label: label.to_string(),
+            status,
+            duration_ms,
+            detail,
+            error,
+        });
+    }
+}
+
+/// Host and port of the API server, defaulting the port from the scheme
+fn host_and_port(config: &Config) -> Result<(String, u16), AppError> {
+    let url = &config.cluster_url;
+    let host = url
+        .host()
+        .ok_or_else(|| AppError::InvalidKubeconfig {
+            message: format!("Server URL {} has no host", url),
+        })?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = url
+        .port_u16()
+        .unwrap_or(if url.scheme_str() == Some("http") { 80 } else { 443 });
+    Ok((host, port))
+}
+
+/// Perform a TLS handshake with the kubeconfig's CA bundle and client certificate
+async fn tls_handshake(config: &Config, stream: TcpStream) -> Result<String, String> {
+    let tls_config = config.rustls_client_config().map_err(|e| e.to_string())?;
+    let (host, _) = host_and_port(config).map_err(|e| e.to_string())?;
+    let name = config.tls_server_name.clone().unwrap_or(host);
+    let server_name = ServerName::try_from(name).map_err(|e| e.to_string())?;
+
+    let tls = TlsConnector::from(Arc::new(tls_config))
+        .connect(server_name, stream)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (_, session) = tls.get_ref();
+    let protocol = session
+        .protocol_version()
+        .map(|v| format!("{:?}", v))
+        .unwrap_or_else(|| "unknown protocol".to_string());
+
+    let verification = if config.accept_invalid_certs {
+        "certificate verification disabled (insecure-skip-tls-verify)"
+    } else if config.root_cert.is_some() {
+        "certificate trusted by the kubeconfig CA"
+    } else {
+        "certificate trusted by the system roots"
+    };
+
+    Ok(format!("{}, {}", protocol, verification))
+}
+
+/// Describe how the kubeconfig authenticates
+fn auth_method(config: &Config) -> String {
+    let auth = &config.auth_info;
+    if let Some(command) = auth.exec.as_ref().and_then(|e| e.command.as_ref()) {
+        format!("exec plugin `{}`", command)
+    } else if auth.token.is_some() || auth.token_file.is_some() {
+        "bearer token".to_string()
+    } else if auth.client_certificate.is_some() || auth.client_certificate_data.is_some() {
+        "client certificate".to_string()
+    } else if auth.auth_provider.is_some() {
+        "auth provider".to_string()
+    } else if auth.username.is_some() {
+        "basic auth".to_string()
+    } else {
+        "anonymous".to_string()
+    }
+}
@@ -1,13 +1,17 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use base64::prelude::{Engine, BASE64_STANDARD};
 use kube::config::{Kubeconfig, NamedContext};
-use std::path::PathBuf;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use crate::types::{DiscoveredCluster, ProviderType};
+use crate::kubernetes::provider;
+use crate::types::{ClusterContext, DiscoveredCluster, ProviderType};
 
-/// Discover Kubernetes clusters from the default kubeconfig
-pub async fn discover_clusters() -> Vec<DiscoveredCluster> {
-    match load_kubeconfig() {
-        Ok(kubeconfig) => parse_kubeconfig(kubeconfig),
+/// Discover Kubernetes clusters from the default kubeconfig files and any extra paths
+pub async fn discover_clusters(extra_paths: &[String]) -> Vec<DiscoveredCluster> {
+    match load_kubeconfig(extra_paths) {
+        Ok((kubeconfig, sources)) => parse_kubeconfig(kubeconfig, &sources),
         Err(e) => {
             log::warn!("Failed to load kubeconfig: {}", e);
             vec![]
@@ -15,97 +19,304 @@ pub async fn discover_clusters() -> Vec<DiscoveredCluster> {
     }
 }
 
-/// Load kubeconfig from the default location
-fn load_kubeconfig() -> Result<Kubeconfig> {
-    let config_path = get_kubeconfig_path()?;
-    
-    if !config_path.exists() {
-        return Err(anyhow::anyhow!("Kubeconfig not found at {:?}", config_path));
+/// List the contexts defined in a kubeconfig string
+pub fn list_contexts(kubeconfig: &str) -> Result<Vec<DiscoveredCluster>> {
+    let kubeconfig = Kubeconfig::from_yaml(kubeconfig)?;
+    Ok(parse_kubeconfig(kubeconfig, &HashMap::new()))
+}
+
+/// Turn a discovered context into a saved cluster with its own self-contained kubeconfig
+///
+/// The kubeconfig holds just that context, its cluster and its user. File
+/// references for certificates and keys are inlined as base64 data so the
+/// saved cluster keeps working if the original files move; exec auth stanzas
+/// are kept as they are.
+pub fn import_context(context_name: &str, extra_paths: &[String]) -> Result<ClusterContext> {
+    let (kubeconfig, sources) = load_kubeconfig(extra_paths)?;
+
+    let named_context = kubeconfig
+        .contexts
+        .iter()
+        .find(|c| c.name == context_name)
+        .ok_or_else(|| anyhow!("Context {} not found in kubeconfig", context_name))?;
+    let context = named_context
+        .context
+        .as_ref()
+        .ok_or_else(|| anyhow!("Context {} has no cluster or user", context_name))?;
+    let named_cluster = kubeconfig
+        .clusters
+        .iter()
+        .find(|c| c.name == context.cluster)
+        .ok_or_else(|| anyhow!("Cluster {} not found in kubeconfig", context.cluster))?;
+    let named_user = context
+        .user
+        .as_ref()
+        .and_then(|user| kubeconfig.auth_infos.iter().find(|a| &a.name == user));
+
+    let discovered = extract_cluster_info(named_context, &kubeconfig, sources.get(context_name))
+        .ok_or_else(|| anyhow!("Cluster {} has no server", context.cluster))?;
+
+    let mut cluster = serde_yaml::to_value(named_cluster)?;
+    inline_file(cluster.get_mut("cluster"), "certificate-authority", "certificate-authority-data")?;
+
+    let mut users = vec![];
+    if let Some(named_user) = named_user {
+        let mut user = serde_yaml::to_value(named_user)?;
+        inline_file(user.get_mut("user"), "client-certificate", "client-certificate-data")?;
+        inline_file(user.get_mut("user"), "client-key", "client-key-data")?;
+        users.push(user);
     }
-    
-    let config_content = std::fs::read_to_string(&config_path)?;
-    let kubeconfig = Kubeconfig::from_yaml(&config_content)?;
-    
-    Ok(kubeconfig)
+
+    let mut flattened = Mapping::new();
+    flattened.insert("apiVersion".into(), "v1".into());
+    flattened.insert("kind".into(), "Config".into());
+    flattened.insert("clusters".into(), Value::Sequence(vec![cluster]));
+    flattened.insert("users".into(), Value::Sequence(users));
+    flattened.insert("contexts".into(), Value::Sequence(vec![serde_yaml::to_value(named_context)?]));
+    flattened.insert("current-context".into(), context_name.into());
+
+    Ok(ClusterContext {
+        id: format!("discovered-{}-{}", context_name, chrono::Utc::now().timestamp_millis()),
+        name: context_name.to_string(),
+        provider: discovered.provider.as_str().to_string(),
+        region: discovered.region.clone().unwrap_or_else(|| "auto-discovered".to_string()),
+        environment: if discovered.is_current_context { "dev" } else { "local" }.to_string(),
+        status: "Connecting...".to_string(),
+        cpu_usage: 0.0,
+        mem_usage: 0.0,
+        node_count: 0,
+        kubeconfig: Some(serde_yaml::to_string(&flattened)?),
+        context_name: Some(context_name.to_string()),
+        cluster_name: None,
+        user_name: None,
+    })
+}
+
+/// Replace a file reference in a kubeconfig section with its base64 contents
+fn inline_file(section: Option<&mut Value>, file_key: &str, data_key: &str) -> Result<()> {
+    let Some(Value::Mapping(section)) = section else {
+        return Ok(());
+    };
+
+    if let Some(Value::String(path)) = section.remove(file_key) {
+        let data = std::fs::read(&path).with_context(|| format!("Failed to read {} {}", file_key, path))?;
+        section.insert(data_key.into(), BASE64_STANDARD.encode(data).into());
+    }
+
+    Ok(())
+}
+
+/// Load and merge every kubeconfig file, returning the file each context was taken from
+///
+/// Files are merged the way kubectl does it: the first file to define a
+/// cluster, user or context name wins, as does the first `current-context`.
+pub fn load_kubeconfig(extra_paths: &[String]) -> Result<(Kubeconfig, HashMap<String, PathBuf>)> {
+    let mut merged = Kubeconfig::default();
+    let mut sources = HashMap::new();
+
+    for path in get_kubeconfig_paths(extra_paths)? {
+        // kubectl silently skips missing entries in KUBECONFIG
+        if !path.is_file() {
+            continue;
+        }
+
+        let mut kubeconfig = match Kubeconfig::read_from(&path) {
+            Ok(kubeconfig) => kubeconfig,
+            Err(e) => {
+                log::warn!("Skipping kubeconfig {:?}: {}", path, e);
+                continue;
+            }
+        };
+
+        if kubeconfig.current_context.as_deref() == Some("") {
+            kubeconfig.current_context = None;
+        }
+        for context in &kubeconfig.contexts {
+            sources.entry(context.name.clone()).or_insert_with(|| path.clone());
+        }
+
+        merged = merged.merge(kubeconfig)?;
+    }
+
+    Ok((merged, sources))
 }
 
-/// Get the default kubeconfig path
-fn get_kubeconfig_path() -> Result<PathBuf> {
-    if let Ok(kube_config_path) = std::env::var("KUBECONFIG") {
-        Ok(PathBuf::from(kube_config_path))
+/// Get the kubeconfig files to read, in merge order
+///
+/// `$KUBECONFIG` is split like a `PATH` list and falls back to `~/.kube/config`.
+/// Extra paths may be files, directories, or a `*` pattern in the last component.
+pub fn get_kubeconfig_paths(extra_paths: &[String]) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = std::env::var_os("KUBECONFIG")
+        .map(|value| {
+            std::env::split_paths(&value)
+                .filter(|p| !p.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if paths.is_empty() {
+        paths.push(home_dir()?.join(".kube").join("config"));
+    }
+
+    for extra in extra_paths {
+        paths.extend(expand_path(extra)?);
+    }
+
+    let mut seen = Vec::new();
+    paths.retain(|p| {
+        let duplicate = seen.contains(p);
+        seen.push(p.clone());
+        !duplicate
+    });
+
+    Ok(paths)
+}
+
+/// Get the user's home directory
+fn home_dir() -> Result<PathBuf> {
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))?;
+    Ok(PathBuf::from(home))
+}
+
+/// Expand `~`, directories and `*` patterns into a sorted list of files
+fn expand_path(path: &str) -> Result<Vec<PathBuf>> {
+    let path = match path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        Some(rest) => home_dir()?.join(rest),
+        None => PathBuf::from(path),
+    };
+
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+
+    let mut files = if file_name.contains('*') {
+        let pattern = file_name.to_string();
+        let dir = path.parent().unwrap_or(Path::new("."));
+        // Like shell globs, `*` doesn't match hidden files such as kubeconfig backups
+        let hidden = pattern.starts_with('.');
+        list_files(dir, |name| (hidden || !name.starts_with('.')) && matches_wildcard(&pattern, name))
+    } else if path.is_dir() {
+        list_files(&path, |name| !name.starts_with('.'))
     } else {
-        let home = std::env::var("HOME")
-            .or_else(|_| std::env::var("USERPROFILE"))?;
-        Ok(PathBuf::from(home).join(".kube").join("config"))
+        vec![path]
+    };
+
+    files.sort();
+    Ok(files)
+}
+
+/// List the regular files in a directory whose names pass a filter
+fn list_files(dir: &Path, filter: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| p.is_file())
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(&filter))
+        .collect()
+}
+
+/// Match a file name against a pattern where `*` stands for any run of characters
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(remaining) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=remaining.len())
+                .filter(|&i| remaining.is_char_boundary(i))
+                .any(|i| matches_wildcard(rest, &remaining[i..]))
+        }
     }
 }
 
 /// Parse kubeconfig and extract all contexts
-fn parse_kubeconfig(kubeconfig: Kubeconfig) -> Vec<DiscoveredCluster> {
-    let current_context = kubeconfig.current_context.clone().unwrap_or_default();
-    let clusters = kubeconfig.clusters.clone();
-    
+fn parse_kubeconfig(kubeconfig: Kubeconfig, sources: &HashMap<String, PathBuf>) -> Vec<DiscoveredCluster> {
     kubeconfig
         .contexts
-        .into_iter()
+        .iter()
         .filter_map(|named_context| {
-            extract_cluster_info(named_context, &current_context, &clusters)
+            let source = sources.get(&named_context.name);
+            extract_cluster_info(named_context, &kubeconfig, source)
         })
         .collect()
 }
 
 /// Extract cluster information from a named context
 fn extract_cluster_info(
-    named_context: NamedContext,
-    current_context: &str,
-    clusters: &[kube::config::NamedCluster],
+    named_context: &NamedContext,
+    kubeconfig: &Kubeconfig,
+    source: Option<&PathBuf>,
 ) -> Option<DiscoveredCluster> {
     let context_name = named_context.name.clone();
-    let context = named_context.context?;
+    let context = named_context.context.as_ref()?;
     let cluster_name = context.cluster.clone();
+    let user_name = context.user.clone().unwrap_or_default();
     
     // Find cluster details
-    let cluster = clusters
+    let cluster = kubeconfig
+        .clusters
         .iter()
         .find(|c| c.name == cluster_name)?;
     
     let server = cluster.cluster.as_ref()?.server.clone()?;
-    let namespace = context.namespace.unwrap_or_else(|| "default".to_string());
-    
-    // Detect provider type based on context/cluster name
-    let provider = detect_provider(&context_name, &cluster_name, &server);
+    let namespace = context.namespace.clone().unwrap_or_else(|| "default".to_string());
+
+    let exec = kubeconfig
+        .auth_infos
+        .iter()
+        .find(|a| a.name == user_name)
+        .and_then(|a| a.auth_info.as_ref())
+        .and_then(|a| a.exec.as_ref());
+
+    let details = provider::detect(&context_name, &cluster_name, &server, exec);
     
     Some(DiscoveredCluster {
-        context_name: context_name.clone(),
+        is_current_context: kubeconfig.current_context.as_deref() == Some(context_name.as_str()),
+        context_name,
         cluster_name,
+        user_name,
         server,
         namespace,
-        is_current_context: context_name == current_context,
-        provider,
+        provider: details.provider.unwrap_or(ProviderType::Local),
+        region: details.region,
+        account: details.account,
+        provider_cluster_name: details.cluster_name,
+        source_file: source.map(|p| p.to_string_lossy().into_owned()),
     })
 }
 
-/// Detect provider type from context name, cluster name, or server URL
-fn detect_provider(context_name: &str, cluster_name: &str, server: &str) -> ProviderType {
-    let combined = format!("{} {} {}", context_name, cluster_name, server).to_lowercase();
-    
-    if combined.contains("docker-desktop") || combined.contains("docker.internal") {
-        ProviderType::Local
-    } else if combined.contains("minikube") {
-        ProviderType::Local
-    } else if combined.contains("kind") {
-        ProviderType::Local
-    } else if combined.contains("k3") {
-        ProviderType::Local
-    } else if combined.contains("microk8s") {
-        ProviderType::Local
-    } else if combined.contains("eks") || combined.contains("amazonaws") {
-        ProviderType::Aws
-    } else if combined.contains("aks") || combined.contains("azure") {
-        ProviderType::Azure
-    } else if combined.contains("gke") || combined.contains("googleapis") {
-        ProviderType::Gcp
-    } else {
-        ProviderType::Local
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_patterns() {
+        let cases = [
+            ("config", "config", true),
+            ("config", "config.yaml", false),
+            ("*", "", true),
+            ("*", "anything", true),
+            ("*.yaml", "dev.yaml", true),
+            ("*.yaml", ".yaml", true),
+            ("*.yaml", "dev.yml", false),
+            ("kind-*", "kind-dev", true),
+            ("kind-*", "k3d-dev", false),
+            ("*-config*", "eks-config.yaml", true),
+            ("*-config*", "config", false),
+            ("a*b*c", "abc", true),
+            ("a*b*c", "axxbyyc", true),
+            ("a*b*c", "axxcyyb", false),
+            ("**", "x", true),
+            ("*é*", "caféine", true),
+            ("*.yaml", "caféyaml", false),
+        ];
+
+        for (pattern, name, expected) in cases {
+            assert_eq!(matches_wildcard(pattern, name), expected, "{} ~ {}", pattern, name);
+        }
     }
 }
@@ -0,0 +1,256 @@
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{DeleteParams, EvictParams, ListParams};
+use kube::Api;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::{AbortHandle, Id, JoinSet};
+
+use crate::error::AppError;
+use crate::kubernetes::nodes;
+use crate::types::{DrainOptions, DrainPodStatus, DrainProgress, DrainSummary};
+
+/// How long to wait before retrying an eviction a PodDisruptionBudget refused
+const EVICTION_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often to check whether an evicted pod has gone
+const DELETION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Annotation the kubelet puts on the API copies of static pods
+const MIRROR_POD_ANNOTATION: &str = "kubernetes.io/config.mirror";
+
+/// Drains in progress, keyed by cluster id and node name, so they can be cancelled
+#[derive(Default)]
+pub struct DrainTasks {
+    tasks: Mutex<HashMap<String, AbortHandle>>,
+}
+
+impl DrainTasks {
+    /// Start and track a drain task; fails if the node is already being drained
+    ///
+    /// `spawn` runs under the lock, so a rejected drain never starts and two
+    /// drains of the same node can't both get going.
+    pub fn start<T>(&self, key: String, spawn: impl FnOnce() -> (T, AbortHandle)) -> Result<(T, Id), AppError> {
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks.get(&key).is_some_and(|t| !t.is_finished()) {
+            return Err(AppError::invalid_request(format!("{} is already being drained", key)));
+        }
+        let (task, abort) = spawn();
+        let id = abort.id();
+        tasks.insert(key, abort);
+        Ok((task, id))
+    }
+
+    /// Stop tracking a finished drain, unless the node has moved on to a newer one
+    pub fn remove(&self, key: &str, id: Id) {
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks.get(key).is_some_and(|t| t.id() == id) {
+            tasks.remove(key);
+        }
+    }
+
+    /// Abort a drain, returning whether one was running
+    pub fn cancel(&self, key: &str) -> bool {
+        match self.tasks.lock().unwrap().remove(key) {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// What to do with a pod on the node being drained
+enum Disposition {
+    Evict,
+    Skip(String),
+    /// The pod can't be evicted safely with the given options
+    Refuse(String),
+}
+
+/// Cordon a node and evict its pods through the Eviction API, like `kubectl drain`
+///
+/// Evictions honour PodDisruptionBudgets: when the API server refuses one with
+/// 429 the eviction is retried until the budget allows it or the drain is
+/// cancelled. DaemonSet and mirror pods are skipped. Pods with emptyDir volumes
+/// or without a controller are refused unless the options allow them, and in
+/// that case nothing is evicted.
+pub async fn drain_node<F>(
+    client: kube::Client,
+    node_name: &str,
+    options: DrainOptions,
+    on_progress: F,
+) -> Result<DrainSummary, AppError>
+where
+    F: Fn(&DrainProgress) + Send + Sync + 'static,
+{
+    nodes::set_unschedulable(client.clone(), node_name, true).await?;
+
+    let pods: Api<Pod> = Api::all(client.clone());
+    let params = ListParams::default().fields(&format!("spec.nodeName={}", node_name));
+    let node_pods = pods.list(&params).await?.items;
+
+    let on_progress = Arc::new(on_progress);
+    let mut to_evict = vec![];
+    let mut refused = vec![];
+    let mut summary = DrainSummary::default();
+
+    for pod in node_pods {
+        let name = pod.metadata.name.clone().unwrap_or_default();
+        let namespace = pod.metadata.namespace.clone().unwrap_or_default();
+        match disposition(&pod, &options) {
+            Disposition::Evict => to_evict.push((namespace, name)),
+            Disposition::Skip(reason) => {
+                summary.skipped += 1;
+                on_progress(&progress(&namespace, &name, DrainPodStatus::Skipped, Some(reason)));
+            }
+            Disposition::Refuse(reason) => refused.push(format!("{}/{}: {}", namespace, name, reason)),
+        }
+    }
+
+    if !refused.is_empty() {
+        return Err(AppError::invalid_request(format!(
+            "Cannot drain {}: {}",
+            node_name,
+            refused.join("; ")
+        )));
+    }
+
+    let delete_options = DeleteParams {
+        grace_period_seconds: options.grace_period_seconds,
+        ..Default::default()
+    };
+    let evict_params = Arc::new(EvictParams {
+        delete_options: Some(delete_options),
+        ..Default::default()
+    });
+
+    let mut evictions = JoinSet::new();
+    for (namespace, name) in to_evict {
+        let pods: Api<Pod> = Api::namespaced(client.clone(), &namespace);
+        let evict_params = evict_params.clone();
+        let on_progress = on_progress.clone();
+
+        evictions.spawn(async move {
+            let result = evict_pod(&pods, &namespace, &name, &evict_params, on_progress.as_ref()).await;
+            match &result {
+                Ok(()) => on_progress(&progress(&namespace, &name, DrainPodStatus::Evicted, None)),
+                Err(e) => on_progress(&progress(&namespace, &name, DrainPodStatus::Failed, Some(e.to_string()))),
+            }
+            result.map_err(|e| format!("{}/{}: {}", namespace, name, e))
+        });
+    }
+
+    let mut failed = vec![];
+    while let Some(joined) = evictions.join_next().await {
+        match joined {
+            Ok(Ok(())) => summary.evicted += 1,
+            Ok(Err(e)) => failed.push(e),
+            Err(e) => failed.push(e.to_string()),
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(summary)
+    } else {
+        Err(AppError::other(format!(
+            "Failed to evict {} pod(s) from {}: {}",
+            failed.len(),
+            node_name,
+            failed.join("; ")
+        )))
+    }
+}
+
+/// Decide whether a pod is evicted, skipped, or blocks the drain
+fn disposition(pod: &Pod, options: &DrainOptions) -> Disposition {
+    let annotations = pod.metadata.annotations.as_ref();
+    if annotations.is_some_and(|a| a.contains_key(MIRROR_POD_ANNOTATION)) {
+        return Disposition::Skip("Mirror pod of a static pod".to_string());
+    }
+
+    let controller = pod
+        .metadata
+        .owner_references
+        .as_ref()
+        .and_then(|owners| owners.iter().find(|o| o.controller == Some(true)));
+    if let Some(owner) = controller.filter(|o| o.kind == "DaemonSet") {
+        return Disposition::Skip(format!("Managed by DaemonSet {}", owner.name));
+    }
+
+    // Finished pods hold nothing worth protecting
+    let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref());
+    if matches!(phase, Some("Succeeded") | Some("Failed")) {
+        return Disposition::Evict;
+    }
+
+    let uses_empty_dir = pod
+        .spec
+        .as_ref()
+        .and_then(|s| s.volumes.as_ref())
+        .is_some_and(|volumes| volumes.iter().any(|v| v.empty_dir.is_some()));
+    if uses_empty_dir && !options.delete_empty_dir_data {
+        return Disposition::Refuse("uses emptyDir volumes whose data would be lost".to_string());
+    }
+
+    if controller.is_none() && !options.force {
+        return Disposition::Refuse("not managed by a controller, so it would not be recreated".to_string());
+    }
+
+    Disposition::Evict
+}
+
+/// Evict one pod, retrying while a disruption budget blocks it, and wait for it to go away
+async fn evict_pod(
+    pods: &Api<Pod>,
+    namespace: &str,
+    name: &str,
+    params: &EvictParams,
+    on_progress: &(dyn Fn(&DrainProgress) + Send + Sync),
+) -> Result<(), AppError> {
+    on_progress(&progress(namespace, name, DrainPodStatus::Evicting, None));
+
+    let mut blocked = false;
+    let uid = loop {
+        let uid = match pods.get_opt(name).await? {
+            Some(pod) => pod.metadata.uid,
+            None => return Ok(()),
+        };
+
+        match pods.evict(name, params).await {
+            Ok(_) => break uid,
+            Err(kube::Error::Api(response)) if response.code == 404 => return Ok(()),
+            Err(kube::Error::Api(response)) if response.code == 429 => {
+                if !blocked {
+                    blocked = true;
+                    on_progress(&progress(
+                        namespace,
+                        name,
+                        DrainPodStatus::Blocked,
+                        Some(response.message),
+                    ));
+                }
+                tokio::time::sleep(EVICTION_RETRY_INTERVAL).await;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    };
+
+    // A controller may recreate a pod under the same name, so compare uids
+    loop {
+        match pods.get_opt(name).await? {
+            Some(pod) if pod.metadata.uid == uid => tokio::time::sleep(DELETION_POLL_INTERVAL).await,
+            _ => return Ok(()),
+        }
+    }
+}
+
+fn progress(namespace: &str, name: &str, status: DrainPodStatus, message: Option<String>) -> DrainProgress {
+    DrainProgress {
+        pod: name.to_string(),
+        namespace: namespace.to_string(),
+        status,
+        message,
+    }
+}
@@ -3,6 +3,9 @@ use k8s_openapi::api::core::v1::Pod;
 use kube::api::AttachParams;
 use kube::Api;
 
+use crate::error::AppError;
+use crate::types::{ExecRequest, ExecResponse};
+
 /// Execute a command in a pod
 pub async fn exec_in_pod(
     client: kube::Client,
@@ -54,9 +57,6 @@ pub async fn exec_in_pod(
     Ok(output)
 }
 
-/// Get list of containers in a pod
-use crate::types::{ExecRequest, ExecResponse};
-
 /// Execute a command in a pod via Tauri command
 pub async fn exec_pod_command(
     client: kube::Client,
@@ -77,7 +77,7 @@ pub async fn exec_pod_command(
         }),
         Err(e) => Ok(ExecResponse {
             output: String::new(),
-            error: Some(e.to_string()),
+            error: Some(AppError::from(e)),
         }),
     }
 }
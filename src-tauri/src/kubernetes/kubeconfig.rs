@@ -0,0 +1,246 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde_yaml::{Mapping, Value};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::kubernetes::discovery;
+
+/// Rename a context, following `current-context` if it pointed at the old name
+pub fn rename_context(old_name: &str, new_name: &str, extra_paths: &[String]) -> Result<()> {
+    let (kubeconfig, _) = discovery::load_kubeconfig(extra_paths)?;
+    if kubeconfig.contexts.iter().any(|c| c.name == new_name) {
+        bail!("A context named {} already exists", new_name);
+    }
+
+    let path = context_file(old_name, extra_paths)?;
+    edit_file(&path, |doc| {
+        let context = find_named_mut(doc, "contexts", old_name)?;
+        context.insert("name".into(), new_name.into());
+        replace_current_context(doc, old_name, new_name);
+        Ok(())
+    })?;
+
+    replace_first_current_context(&path, old_name, new_name)
+}
+
+/// Delete a context together with any cluster and user entries no other context uses
+///
+/// Contexts in the other merged kubeconfig files count as users too, since
+/// they may refer to entries defined in this one.
+pub fn delete_context(name: &str, extra_paths: &[String]) -> Result<()> {
+    let path = context_file(name, extra_paths)?;
+    let others: Vec<Mapping> = discovery::get_kubeconfig_paths(extra_paths)?
+        .into_iter()
+        .filter(|p| *p != path && p.is_file())
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .filter_map(|contents| serde_yaml::from_str(&contents).ok())
+        .collect();
+
+    edit_file(&path, |doc| {
+        let context = find_named_mut(doc, "contexts", name)?.clone();
+        remove_named(doc, "contexts", name);
+
+        for (section, key) in [("clusters", "cluster"), ("users", "user")] {
+            let Some(entry) = context
+                .get("context")
+                .and_then(|c| c.get(key))
+                .and_then(Value::as_str)
+            else {
+                continue;
+            };
+            let referenced = std::iter::once(&*doc).chain(&others).any(|d| is_referenced(d, key, entry));
+            if !referenced {
+                remove_named(doc, section, entry);
+            }
+        }
+
+        replace_current_context(doc, name, "");
+        Ok(())
+    })?;
+
+    replace_first_current_context(&path, name, "")
+}
+
+/// Set the default namespace of a context
+pub fn set_context_namespace(name: &str, namespace: &str, extra_paths: &[String]) -> Result<()> {
+    edit_file(&context_file(name, extra_paths)?, |doc| {
+        let context = find_named_mut(doc, "contexts", name)?;
+        let fields = context
+            .entry("context".into())
+            .or_insert_with(|| Value::Mapping(Mapping::new()))
+            .as_mapping_mut()
+            .ok_or_else(|| anyhow!("Context {} is malformed", name))?;
+
+        if namespace.is_empty() {
+            fields.remove("namespace");
+        } else {
+            fields.insert("namespace".into(), namespace.into());
+        }
+        Ok(())
+    })
+}
+
+/// Switch `current-context`, written to the first kubeconfig file like kubectl does
+pub fn use_context(name: &str, extra_paths: &[String]) -> Result<()> {
+    let (kubeconfig, _) = discovery::load_kubeconfig(extra_paths)?;
+    if !kubeconfig.contexts.iter().any(|c| c.name == name) {
+        bail!("Context {} not found in kubeconfig", name);
+    }
+
+    let path = first_kubeconfig_file()?
+        .ok_or_else(|| anyhow!("No kubeconfig file to write current-context to"))?;
+
+    edit_file(&path, |doc| {
+        doc.insert("current-context".into(), name.into());
+        Ok(())
+    })
+}
+
+/// The first existing kubeconfig file, which kubectl reads `current-context` from
+fn first_kubeconfig_file() -> Result<Option<PathBuf>> {
+    Ok(discovery::get_kubeconfig_paths(&[])?.into_iter().find(|p| p.is_file()))
+}
+
+/// Point `current-context` of the first kubeconfig file elsewhere if it names a context
+///
+/// `edited` is the file that defines the context, which was already updated.
+fn replace_first_current_context(edited: &Path, from: &str, to: &str) -> Result<()> {
+    match first_kubeconfig_file()? {
+        Some(first) if first != edited => edit_file(&first, |doc| {
+            replace_current_context(doc, from, to);
+            Ok(())
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn replace_current_context(doc: &mut Mapping, from: &str, to: &str) {
+    if doc.get("current-context").and_then(Value::as_str) == Some(from) {
+        doc.insert("current-context".into(), to.into());
+    }
+}
+
+/// The kubeconfig file that defines a context
+fn context_file(context_name: &str, extra_paths: &[String]) -> Result<PathBuf> {
+    let (_, mut sources) = discovery::load_kubeconfig(extra_paths)?;
+    sources
+        .remove(context_name)
+        .ok_or_else(|| anyhow!("Context {} not found in kubeconfig", context_name))
+}
+
+/// Apply an edit to a kubeconfig file, keeping every field it doesn't touch
+///
+/// The previous contents are saved next to the file in a hidden backup, and
+/// the new contents are written to a temporary file and renamed into place so
+/// a crash never leaves a half-written kubeconfig behind. Nothing is written
+/// when the edit leaves the file unchanged.
+fn edit_file<F>(path: &Path, edit: F) -> Result<()>
+where
+    F: FnOnce(&mut Mapping) -> Result<()>,
+{
+    let original = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let mut doc: Value = serde_yaml::from_str(&original).with_context(|| format!("Failed to parse {:?}", path))?;
+    let mapping = doc
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow!("{:?} is not a kubeconfig", path))?;
+
+    let before = mapping.clone();
+    edit(mapping)?;
+    if *mapping == before {
+        return Ok(());
+    }
+
+    let updated = serde_yaml::to_string(&doc)?;
+    backup_file(path)?;
+    write_atomically(path, updated.as_bytes())
+}
+
+/// Copy a file to `.<name>.backup-<timestamp>` in the same directory
+///
+/// The leading dot keeps backups out of directories and patterns listed as
+/// extra kubeconfig paths, which would otherwise merge them back in.
+fn backup_file(path: &Path) -> Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f");
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let backup = dir.join(format!(".{}.backup-{}", file_name, timestamp));
+
+    fs::copy(path, &backup).with_context(|| format!("Failed to back up {:?}", path))?;
+    Ok(backup)
+}
+
+/// Replace a file's contents through a temporary file and rename
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = dir.join(format!(".{}.tmp-{}", file_name, std::process::id()));
+
+    let result = (|| -> Result<()> {
+        let mut temp = fs::File::create(&temp_path)?;
+        // Kubeconfigs hold credentials, so keep the original permissions
+        fs::set_permissions(&temp_path, fs::metadata(path)?.permissions())?;
+        temp.write_all(contents)?;
+        temp.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result.with_context(|| format!("Failed to write {:?}", path))
+}
+
+/// Find the entry with a given name in a named list such as `contexts`
+fn find_named_mut<'a>(doc: &'a mut Mapping, section: &str, name: &str) -> Result<&'a mut Mapping> {
+    doc.get_mut(section)
+        .and_then(Value::as_sequence_mut)
+        .and_then(|entries| {
+            entries
+                .iter_mut()
+                .filter_map(Value::as_mapping_mut)
+                .find(|entry| entry.get("name").and_then(Value::as_str) == Some(name))
+        })
+        .ok_or_else(|| anyhow!("{} entry {} not found", section, name))
+}
+
+/// Remove the entry with a given name from a named list
+fn remove_named(doc: &mut Mapping, section: &str, name: &str) {
+    if let Some(entries) = doc.get_mut(section).and_then(Value::as_sequence_mut) {
+        entries.retain(|entry| entry.get("name").and_then(Value::as_str) != Some(name));
+    }
+}
+
+/// Whether any remaining context refers to a cluster or user entry
+fn is_referenced(doc: &Mapping, key: &str, name: &str) -> bool {
+    doc.get("contexts")
+        .and_then(Value::as_sequence)
+        .is_some_and(|contexts| {
+            contexts.iter().any(|c| {
+                c.get("context").and_then(|c| c.get(key)).and_then(Value::as_str) == Some(name)
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backups_are_not_merged_back_in() {
+        let dir = std::env::temp_dir().join(format!("kubeconfig-backup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("config");
+        fs::write(&config, "apiVersion: v1\nkind: Config\n").unwrap();
+        let backup = backup_file(&config).unwrap();
+
+        for extra in [dir.clone(), dir.join("*"), dir.join("config*")] {
+            let paths = discovery::get_kubeconfig_paths(&[extra.to_string_lossy().into_owned()]).unwrap();
+            assert!(paths.contains(&config), "{:?}", extra);
+            assert!(!paths.contains(&backup), "{:?}", extra);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
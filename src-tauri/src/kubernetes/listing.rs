@@ -0,0 +1,201 @@
+use k8s_openapi::api::core::v1::Namespace;
+use k8s_openapi::{ClusterResourceScope, NamespaceResourceScope};
+use kube::api::{ListParams, ObjectList};
+use kube::Api;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+use crate::types::ListOptions;
+
+/// Prefix of continue tokens that resume a list spanning several namespaces
+const NAMESPACE_TOKEN_PREFIX: &str = "ns:";
+
+/// One page of a list and the token to request the next one
+pub struct Page<K> {
+    pub items: Vec<K>,
+    pub continue_token: Option<String>,
+}
+
+/// List a namespaced resource with the caller's namespaces, selectors and page
+///
+/// Without namespaces the list is cluster-wide. When RBAC forbids that, the
+/// namespaces the user can see (or the kubeconfig's default namespace if
+/// namespaces can't be listed either) are listed one by one instead, skipping
+/// the ones that are forbidden too. Lists spanning several namespaces are
+/// paged through in order, with the namespace position kept in the token.
+pub async fn list<K>(client: &kube::Client, options: &ListOptions) -> Result<Page<K>, kube::Error>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope> + Clone + DeserializeOwned + Debug,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    match options.namespaces.as_slice() {
+        [] => {
+            let resuming_fallback = options
+                .continue_token
+                .as_deref()
+                .is_some_and(|t| t.starts_with(NAMESPACE_TOKEN_PREFIX));
+            if !resuming_fallback {
+                let params = params(options, options.limit, options.continue_token.clone());
+                match Api::<K>::all(client.clone()).list(&params).await {
+                    Ok(list) => return Ok(page(list)),
+                    Err(kube::Error::Api(response)) if response.code == 403 => {
+                        log::info!("Cluster-wide list forbidden, listing per namespace: {}", response.message)
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            let namespaces = visible_namespaces(client).await;
+            list_in_namespaces(client, &namespaces, options, true).await
+        }
+        [namespace] => {
+            let params = params(options, options.limit, options.continue_token.clone());
+            let list = Api::<K>::namespaced(client.clone(), namespace).list(&params).await?;
+            Ok(page(list))
+        }
+        namespaces => list_in_namespaces(client, namespaces, options, false).await,
+    }
+}
+
+/// List a cluster-scoped resource such as Nodes; namespaces are ignored
+pub async fn list_cluster<K>(client: &kube::Client, options: &ListOptions) -> Result<Page<K>, kube::Error>
+where
+    K: kube::Resource<Scope = ClusterResourceScope> + Clone + DeserializeOwned + Debug,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let params = params(options, options.limit, options.continue_token.clone());
+    Ok(page(Api::<K>::all(client.clone()).list(&params).await?))
+}
+
+/// List namespace by namespace until the page is full
+async fn list_in_namespaces<K>(
+    client: &kube::Client,
+    namespaces: &[String],
+    options: &ListOptions,
+    skip_forbidden: bool,
+) -> Result<Page<K>, kube::Error>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope> + Clone + DeserializeOwned + Debug,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let (mut index, mut token) = parse_token(options.continue_token.as_deref());
+    let mut items = vec![];
+    let mut forbidden = None;
+    let mut forbidden_count = 0;
+
+    while index < namespaces.len() {
+        let remaining = options.limit.map(|limit| limit.saturating_sub(items.len() as u32));
+        if remaining == Some(0) {
+            return Ok(Page {
+                items,
+                continue_token: Some(encode_token(index, None)),
+            });
+        }
+
+        let api = Api::<K>::namespaced(client.clone(), &namespaces[index]);
+        match api.list(&params(options, remaining, token.take())).await {
+            Ok(list) => {
+                items.extend(list.items);
+                if let Some(next) = list.metadata.continue_.filter(|t| !t.is_empty()) {
+                    return Ok(Page {
+                        items,
+                        continue_token: Some(encode_token(index, Some(next))),
+                    });
+                }
+            }
+            Err(kube::Error::Api(response)) if skip_forbidden && response.code == 403 => {
+                forbidden_count += 1;
+                forbidden.get_or_insert(kube::Error::Api(response));
+            }
+            Err(e) => return Err(e),
+        }
+        index += 1;
+    }
+
+    // Only an error when nothing at all could be listed
+    match forbidden {
+        Some(e) if forbidden_count == namespaces.len() => Err(e),
+        _ => Ok(Page {
+            items,
+            continue_token: None,
+        }),
+    }
+}
+
+/// Namespaces the user can list, or the kubeconfig's default namespace
+async fn visible_namespaces(client: &kube::Client) -> Vec<String> {
+    match Api::<Namespace>::all(client.clone()).list(&ListParams::default()).await {
+        Ok(list) => {
+            let mut names: Vec<String> = list.items.into_iter().filter_map(|n| n.metadata.name).collect();
+            names.sort();
+            names
+        }
+        Err(e) => {
+            log::info!("Could not list namespaces, using the default namespace: {}", e);
+            vec![client.default_namespace().to_string()]
+        }
+    }
+}
+
+fn params(options: &ListOptions, limit: Option<u32>, continue_token: Option<String>) -> ListParams {
+    ListParams {
+        label_selector: options.label_selector.clone().filter(|s| !s.is_empty()),
+        field_selector: options.field_selector.clone().filter(|s| !s.is_empty()),
+        limit,
+        continue_token,
+        ..Default::default()
+    }
+}
+
+fn page<K: Clone>(list: ObjectList<K>) -> Page<K> {
+    Page {
+        continue_token: list.metadata.continue_.filter(|t| !t.is_empty()),
+        items: list.items,
+    }
+}
+
+/// Token of the form `ns:<namespace index>:<server continue token>`
+fn encode_token(index: usize, token: Option<String>) -> String {
+    format!("{}{}:{}", NAMESPACE_TOKEN_PREFIX, index, token.unwrap_or_default())
+}
+
+fn parse_token(token: Option<&str>) -> (usize, Option<String>) {
+    let Some(rest) = token.and_then(|t| t.strip_prefix(NAMESPACE_TOKEN_PREFIX)) else {
+        return (0, token.map(str::to_string));
+    };
+    match rest.split_once(':') {
+        Some((index, token)) => (
+            index.parse().unwrap_or(0),
+            Some(token.to_string()).filter(|t| !t.is_empty()),
+        ),
+        None => (0, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespace_continue_tokens() {
+        let cases = [
+            (None, (0, None)),
+            // A plain server token from a single-namespace or cluster-wide list
+            (Some("eyJ2IjoibWV0YS"), (0, Some("eyJ2IjoibWV0YS"))),
+            (Some("ns:"), (0, None)),
+            (Some("ns:2:"), (2, None)),
+            (Some("ns:1:abc"), (1, Some("abc"))),
+            (Some("ns:3:a:b"), (3, Some("a:b"))),
+            (Some("ns:x:abc"), (0, Some("abc"))),
+        ];
+
+        for (token, (index, inner)) in cases {
+            assert_eq!(parse_token(token), (index, inner.map(str::to_string)), "{:?}", token);
+        }
+
+        for (index, inner) in [(0, None), (2, Some("abc")), (7, Some("a:b"))] {
+            let token = encode_token(index, inner.map(str::to_string));
+            assert!(token.starts_with(NAMESPACE_TOKEN_PREFIX), "{}", token);
+            assert_eq!(parse_token(Some(&token)), (index, inner.map(str::to_string)), "{}", token);
+        }
+    }
+}
@@ -0,0 +1,811 @@
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use futures::{AsyncBufReadExt, AsyncReadExt, FutureExt, StreamExt};
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{ContainerState, ContainerStatus, Pod};
+use kube::api::{ListParams, LogParams, WatchEvent, WatchParams};
+use kube::Api;
+use regex::{Regex, RegexSet};
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+use crate::error::AppError;
+use crate::kubernetes::subscriptions::RetryDelay;
+use crate::kubernetes::workloads;
+use crate::types::{LogEvent, LogLine, LogOptions, LogTailRequest, PodLogs};
+
+/// Annotation naming the container kubectl picks when none is given
+const DEFAULT_CONTAINER_ANNOTATION: &str = "kubectl.kubernetes.io/default-container";
+
+/// Fetch the log of one container of a pod
+///
+/// Without a container, uses the pod's default container annotation or its
+/// first container, like kubectl. Init and ephemeral containers can be named
+/// explicitly.
+pub async fn get_pod_logs(
+    client: kube::Client,
+    namespace: &str,
+    pod_name: &str,
+    options: &LogOptions,
+) -> Result<PodLogs, AppError> {
+    let pods: Api<Pod> = Api::namespaced(client, namespace);
+    let pod = pods.get(pod_name).await?;
+    let container = resolve_container(&pod, options.container.as_deref())?;
+
+    let params = log_params(Some(&container), options, false)?;
+    // Read as bytes: `limitBytes` can cut a multi-byte character in half,
+    // which `Api::logs` would reject as invalid UTF-8
+    let mut bytes = vec![];
+    pods.log_stream(pod_name, &params)
+        .await?
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(kube::Error::ReadEvents)?;
+    let truncated = options.limit_bytes.is_some_and(|limit| bytes.len() as i64 >= limit);
+
+    Ok(PodLogs {
+        container,
+        logs: String::from_utf8_lossy(&bytes).into_owned(),
+        truncated,
+    })
+}
+
+/// The requested container, checked against the pod, or the default one
+pub fn resolve_container(pod: &Pod, requested: Option<&str>) -> Result<String, AppError> {
+    let containers = container_names(pod);
+    let pod_name = pod.metadata.name.as_deref().unwrap_or_default();
+
+    if let Some(requested) = requested.filter(|c| !c.is_empty()) {
+        if containers.iter().any(|c| c == requested) {
+            return Ok(requested.to_string());
+        }
+        return Err(AppError::NotFound {
+            message: format!(
+                "Container {} is not in pod {}; choose one of: {}",
+                requested,
+                pod_name,
+                containers.join(", ")
+            ),
+        });
+    }
+
+    let annotated = pod
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|a| a.get(DEFAULT_CONTAINER_ANNOTATION))
+        .filter(|name| containers.contains(name));
+    annotated
+        .or(containers.first())
+        .cloned()
+        .ok_or_else(|| AppError::NotFound {
+            message: format!("Pod {} has no containers", pod_name),
+        })
+}
+
+pub fn log_params(container: Option<&str>, options: &LogOptions, follow: bool) -> Result<LogParams, AppError> {
+    if options.since_seconds.is_some() && options.since_time.is_some() {
+        return Err(AppError::invalid_request("Use either sinceSeconds or sinceTime, not both"));
+    }
+    let since_time = options
+        .since_time
+        .as_deref()
+        .map(|time| {
+            DateTime::parse_from_rfc3339(time)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| AppError::invalid_request(format!("Invalid sinceTime {}: {}", time, e)))
+        })
+        .transpose()?;
+
+    Ok(LogParams {
+        container: container.map(str::to_string),
+        follow,
+        limit_bytes: options.limit_bytes,
+        previous: options.previous,
+        since_seconds: options.since_seconds,
+        since_time,
+        tail_lines: options.tail_lines,
+        timestamps: options.timestamps,
+        ..Default::default()
+    })
+}
+
+/// Longest a batch of lines is held back before it is sent
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Most lines sent per flush; beyond that, reading pauses until the next one
+const MAX_BATCH_LINES: usize = 1000;
+
+/// Lines queued between the log readers and the sender
+const LINE_BUFFER: usize = 5000;
+
+/// Server-side timeout of each pod watch; the watch is resumed afterwards
+const WATCH_TIMEOUT_SECS: u32 = 290;
+
+/// How often the pod is checked while waiting for a container to (re)start
+const RESTART_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the log readers hand to the sender
+enum Output {
+    Line(LogLine),
+    Event(LogEvent),
+}
+
+/// Why a container's log stream ended
+enum NextRun {
+    /// The same run is still going; the connection just dropped
+    Resume,
+    Restarted(i32),
+    Ended(String),
+}
+
+/// Build the task that follows a container's log and reports batches of lines
+///
+/// The pod and options are checked up front so mistakes are reported before
+/// anything is spawned. The task reconnects when the container restarts and
+/// ends when the pod is deleted or the container won't run again, or when
+/// `on_event` returns false because the frontend closed the channel.
+pub async fn follow_pod_logs<F>(
+    client: kube::Client,
+    namespace: &str,
+    pod_name: &str,
+    options: &LogOptions,
+    on_event: F,
+) -> Result<BoxFuture<'static, ()>, AppError>
+where
+    F: FnMut(LogEvent) -> bool + Send + 'static,
+{
+    if options.previous {
+        return Err(AppError::invalid_request("The log of a previous container can't be followed"));
+    }
+    let pods: Api<Pod> = Api::namespaced(client, namespace);
+    let pod = pods.get(pod_name).await?;
+    let container = resolve_container(&pod, options.container.as_deref())?;
+    let params = log_params(Some(&container), options, true)?;
+
+    let (tx, rx) = mpsc::channel(LINE_BUFFER);
+    let follow = follow_container(pods, pod, container, params, tx);
+    Ok(until_closed(follow, forward(rx, None, on_event)).boxed())
+}
+
+/// Build the task that follows every container of the pods a selector matches
+///
+/// Like stern: pods that appear later are picked up with their whole log, and
+/// a pod's streams end on their own once it is deleted. Lines are filtered in
+/// the backend, so only lines matching an include pattern (if any) and no
+/// exclude pattern reach the frontend.
+pub async fn tail_pod_logs<F>(
+    client: kube::Client,
+    request: &LogTailRequest,
+    options: &LogOptions,
+    on_event: F,
+) -> Result<BoxFuture<'static, ()>, AppError>
+where
+    F: FnMut(LogEvent) -> bool + Send + 'static,
+{
+    if options.previous {
+        return Err(AppError::invalid_request("The log of a previous container can't be followed"));
+    }
+    let selector = match (
+        request.label_selector.as_deref().filter(|s| !s.is_empty()),
+        request.deployment.as_deref().filter(|d| !d.is_empty()),
+    ) {
+        (Some(selector), None) => selector.to_string(),
+        (None, Some(deployment)) => {
+            let deployments: Api<Deployment> = Api::namespaced(client.clone(), &request.namespace);
+            let selector = deployments
+                .get(deployment)
+                .await?
+                .spec
+                .map(|s| workloads::format_selector(&s.selector))
+                .unwrap_or_default();
+            if selector.is_empty() {
+                return Err(AppError::invalid_request(format!("Deployment {} has no selector", deployment)));
+            }
+            selector
+        }
+        _ => return Err(AppError::invalid_request("Give either a label selector or a Deployment")),
+    };
+    let filter = LineFilter::new(&request.include, &request.exclude)?;
+    let params = log_params(None, options, true)?;
+
+    let pods: Api<Pod> = Api::namespaced(client, &request.namespace);
+    let (tx, rx) = mpsc::channel(LINE_BUFFER);
+    let discover = follow_matching_pods(pods, selector, options.container.clone(), params, tx);
+    Ok(until_closed(discover, forward(rx, filter, on_event)).boxed())
+}
+
+/// Keep a follower running for every container of every matching pod
+async fn follow_matching_pods(
+    pods: Api<Pod>,
+    selector: String,
+    container: Option<String>,
+    params: LogParams,
+    tx: mpsc::Sender<Output>,
+) {
+    let list_params = ListParams::default().labels(&selector);
+    let watch_params = WatchParams {
+        label_selector: Some(selector),
+        timeout: Some(WATCH_TIMEOUT_SECS),
+        bookmarks: true,
+        ..Default::default()
+    };
+    let mut followers = JoinSet::new();
+    let mut following: HashSet<(String, String)> = HashSet::new();
+    let mut retry = RetryDelay::default();
+    // The tail and since options apply to the pods running at the start;
+    // pods created later are followed from their first line
+    let later_params = LogParams {
+        tail_lines: None,
+        since_seconds: None,
+        since_time: None,
+        ..params.clone()
+    };
+    let mut start_params = &params;
+
+    'relist: loop {
+        let list = match pods.list(&list_params).await {
+            Ok(list) => list,
+            Err(e) => {
+                if !send_error(&tx, e).await {
+                    return;
+                }
+                retry.wait().await;
+                continue;
+            }
+        };
+        retry.reset();
+
+        let live: HashSet<&str> = list.items.iter().filter_map(|p| p.metadata.uid.as_deref()).collect();
+        following.retain(|(uid, _)| live.contains(uid.as_str()));
+        for pod in &list.items {
+            if !follow_pod(&pods, pod, container.as_deref(), start_params, &mut following, &mut followers, &tx).await {
+                return;
+            }
+        }
+        start_params = &later_params;
+
+        let mut resource_version = list.metadata.resource_version.clone().unwrap_or_default();
+        loop {
+            let stream = match pods.watch(&watch_params, &resource_version).await {
+                Ok(stream) => stream,
+                Err(kube::Error::Api(response)) if response.code == 410 => continue 'relist,
+                Err(e) => {
+                    if !send_error(&tx, e).await {
+                        return;
+                    }
+                    retry.wait().await;
+                    continue;
+                }
+            };
+            let mut stream = std::pin::pin!(stream);
+
+            while let Some(event) = stream.next().await {
+                match event {
+                    Ok(WatchEvent::Added(pod)) | Ok(WatchEvent::Modified(pod)) => {
+                        retry.reset();
+                        resource_version = pod.metadata.resource_version.clone().unwrap_or(resource_version);
+                        let started =
+                            follow_pod(&pods, &pod, container.as_deref(), start_params, &mut following, &mut followers, &tx);
+                        if !started.await {
+                            return;
+                        }
+                    }
+                    // The pod's followers notice the deletion and end by themselves
+                    Ok(WatchEvent::Deleted(pod)) => {
+                        resource_version = pod.metadata.resource_version.clone().unwrap_or(resource_version);
+                        following.retain(|(uid, _)| pod.metadata.uid.as_deref() != Some(uid.as_str()));
+                    }
+                    Ok(WatchEvent::Bookmark(bookmark)) => resource_version = bookmark.metadata.resource_version,
+                    Ok(WatchEvent::Error(response)) if response.code == 410 => continue 'relist,
+                    Ok(WatchEvent::Error(response)) => {
+                        if !send_error(&tx, kube::Error::Api(response)).await {
+                            return;
+                        }
+                        retry.wait().await;
+                        break;
+                    }
+                    Err(kube::Error::Api(response)) if response.code == 410 => continue 'relist,
+                    Err(e) => {
+                        if !send_error(&tx, e).await {
+                            return;
+                        }
+                        retry.wait().await;
+                        break;
+                    }
+                }
+            }
+            // Reap followers that finished so the set doesn't grow
+            while followers.try_join_next().is_some() {}
+        }
+    }
+}
+
+/// Start following the containers of a pod that aren't followed yet
+///
+/// Returns false once the frontend has gone away.
+async fn follow_pod(
+    pods: &Api<Pod>,
+    pod: &Pod,
+    only_container: Option<&str>,
+    params: &LogParams,
+    following: &mut HashSet<(String, String)>,
+    followers: &mut JoinSet<()>,
+    tx: &mpsc::Sender<Output>,
+) -> bool {
+    let Some(uid) = pod.metadata.uid.clone() else { return true };
+    let pod_name = pod.metadata.name.clone().unwrap_or_default();
+    let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref());
+    if matches!(phase, Some("Succeeded") | Some("Failed")) {
+        return true;
+    }
+
+    for container in container_names(pod) {
+        if only_container.is_some_and(|c| c != container) || !following.insert((uid.clone(), container.clone())) {
+            continue;
+        }
+        let params = LogParams {
+            container: Some(container.clone()),
+            ..params.clone()
+        };
+
+        let event = LogEvent::Started {
+            pod: pod_name.clone(),
+            container: container.clone(),
+        };
+        if tx.send(Output::Event(event)).await.is_err() {
+            return false;
+        }
+        followers.spawn(follow_container(pods.clone(), pod.clone(), container, params, tx.clone()));
+    }
+    true
+}
+
+async fn send_error(tx: &mpsc::Sender<Output>, error: kube::Error) -> bool {
+    let event = LogEvent::Error {
+        pod: None,
+        container: None,
+        error: error.into(),
+    };
+    tx.send(Output::Event(event)).await.is_ok()
+}
+
+/// Include and exclude patterns applied to every line
+pub struct LineFilter {
+    include: Option<RegexSet>,
+    exclude: Option<RegexSet>,
+}
+
+impl LineFilter {
+    /// None when there are no patterns, so lines skip the check entirely
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Option<Self>, AppError> {
+        if include.is_empty() && exclude.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(LineFilter {
+            include: pattern_set(include)?,
+            exclude: pattern_set(exclude)?,
+        }))
+    }
+
+    pub fn keeps(&self, line: &str) -> bool {
+        self.include.as_ref().map_or(true, |set| set.is_match(line))
+            && !self.exclude.as_ref().is_some_and(|set| set.is_match(line))
+    }
+}
+
+fn pattern_set(patterns: &[String]) -> Result<Option<RegexSet>, AppError> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    // Compiled one by one first so the error names the bad pattern
+    for pattern in patterns {
+        Regex::new(pattern).map_err(|e| AppError::invalid_request(format!("Invalid pattern {}: {}", pattern, e)))?;
+    }
+    RegexSet::new(patterns)
+        .map(Some)
+        .map_err(|e| AppError::invalid_request(e.to_string()))
+}
+
+/// Follow one container across restarts until its pod goes away
+async fn follow_container(
+    pods: Api<Pod>,
+    pod: Pod,
+    container: String,
+    mut params: LogParams,
+    tx: mpsc::Sender<Output>,
+) {
+    let pod_name = pod.metadata.name.clone().unwrap_or_default();
+    let uid = pod.metadata.uid.clone();
+    let mut restart_count = container_status(&pod, &container).map(|s| s.restart_count).unwrap_or(0);
+    let mut resume = ResumePoint::default();
+    let mut retry = RetryDelay::default();
+
+    // Timestamps are always requested so reconnects can skip lines already sent
+    params.timestamps = true;
+
+    loop {
+        match pods.log_stream(&pod_name, &params).await {
+            Ok(stream) => {
+                retry.reset();
+                let mut lines = stream.lines();
+                while let Some(line) = lines.next().await {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(e) => {
+                            log::info!("Log stream of {}/{} interrupted: {}", pod_name, container, e);
+                            break;
+                        }
+                    };
+                    let (timestamp, message) = split_timestamp(&line);
+                    if !resume.keeps(timestamp) {
+                        continue;
+                    }
+                    let line = LogLine {
+                        pod: pod_name.clone(),
+                        container: container.clone(),
+                        timestamp: timestamp.map(|t| t.to_rfc3339()),
+                        message: message.to_string(),
+                    };
+                    if tx.send(Output::Line(line)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            // The container hasn't started yet; wait for it below
+            Err(kube::Error::Api(response)) if response.code == 400 => {}
+            Err(e) => {
+                let event = LogEvent::Error {
+                    pod: Some(pod_name.clone()),
+                    container: Some(container.clone()),
+                    error: e.into(),
+                };
+                if tx.send(Output::Event(event)).await.is_err() {
+                    return;
+                }
+                retry.wait().await;
+            }
+        }
+
+        match next_run(&pods, &pod_name, uid.as_deref(), &container, restart_count).await {
+            NextRun::Resume => {}
+            NextRun::Restarted(count) => {
+                restart_count = count;
+                let event = LogEvent::Restarted {
+                    pod: pod_name.clone(),
+                    container: container.clone(),
+                    restart_count: count,
+                };
+                if tx.send(Output::Event(event)).await.is_err() {
+                    return;
+                }
+            }
+            NextRun::Ended(reason) => {
+                let event = LogEvent::Ended {
+                    pod: pod_name.clone(),
+                    container: container.clone(),
+                    reason,
+                };
+                let _ = tx.send(Output::Event(event)).await;
+                return;
+            }
+        }
+
+        // Pick up where the last stream left off; the tail and since options
+        // only apply to the first connection
+        params.tail_lines = None;
+        params.limit_bytes = None;
+        params.since_seconds = None;
+        params.since_time = resume.reconnect();
+    }
+}
+
+/// Wait until the container runs again, or find out it never will
+async fn next_run(pods: &Api<Pod>, pod_name: &str, uid: Option<&str>, container: &str, restart_count: i32) -> NextRun {
+    loop {
+        match pods.get_opt(pod_name).await {
+            Ok(None) => return NextRun::Ended("Pod deleted".to_string()),
+            Ok(Some(pod)) if pod.metadata.uid.as_deref() != uid => {
+                return NextRun::Ended("Pod deleted".to_string());
+            }
+            Ok(Some(pod)) => {
+                match container_status(&pod, container) {
+                    Some(status) => {
+                        let state = status.state.clone().unwrap_or_default();
+                        if status.restart_count > restart_count {
+                            return NextRun::Restarted(status.restart_count);
+                        }
+                        if state.running.is_some() {
+                            return NextRun::Resume;
+                        }
+                        if state.terminated.is_some() && !will_restart(&pod, container, &state) {
+                            return NextRun::Ended("Container exited".to_string());
+                        }
+                    }
+                    // Statuses appear once the pod is scheduled; until then keep waiting
+                    None if container_names(&pod).iter().any(|c| c == container) => {}
+                    None => return NextRun::Ended("Container removed".to_string()),
+                }
+            }
+            Err(e) => log::info!("Could not check pod {} for restarts: {}", pod_name, e),
+        }
+        tokio::time::sleep(RESTART_POLL_INTERVAL).await;
+    }
+}
+
+/// Names of all containers in a pod whose log can be read: regular ones
+/// first, then init and ephemeral containers
+fn container_names(pod: &Pod) -> Vec<String> {
+    let Some(spec) = pod.spec.as_ref() else { return vec![] };
+
+    spec.containers
+        .iter()
+        .map(|c| c.name.clone())
+        .chain(spec.init_containers.iter().flatten().map(|c| c.name.clone()))
+        .chain(spec.ephemeral_containers.iter().flatten().map(|c| c.name.clone()))
+        .collect()
+}
+
+/// Whether the kubelet will start a terminated container again
+fn will_restart(pod: &Pod, container: &str, state: &ContainerState) -> bool {
+    let Some(spec) = pod.spec.as_ref() else { return false };
+    let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref());
+    if matches!(phase, Some("Succeeded") | Some("Failed")) || pod.metadata.deletion_timestamp.is_some() {
+        return false;
+    }
+    if spec.ephemeral_containers.iter().flatten().any(|c| c.name == container) {
+        return false;
+    }
+
+    let succeeded = state.terminated.as_ref().is_some_and(|t| t.exit_code == 0);
+    if let Some(init) = spec.init_containers.iter().flatten().find(|c| c.name == container) {
+        // Sidecars always restart; other init containers only until they succeed
+        if init.restart_policy.as_deref() == Some("Always") {
+            return true;
+        }
+        if succeeded {
+            return false;
+        }
+    }
+    match spec.restart_policy.as_deref() {
+        Some("Never") => false,
+        Some("OnFailure") => !succeeded,
+        _ => true,
+    }
+}
+
+/// Status of a regular, init or ephemeral container
+fn container_status<'a>(pod: &'a Pod, container: &str) -> Option<&'a ContainerStatus> {
+    let status = pod.status.as_ref()?;
+    status
+        .container_statuses
+        .iter()
+        .chain(&status.init_container_statuses)
+        .chain(&status.ephemeral_container_statuses)
+        .flatten()
+        .find(|s| s.name == container)
+}
+
+/// Split the RFC 3339 timestamp the API server prefixes lines with
+fn split_timestamp(line: &str) -> (Option<DateTime<Utc>>, &str) {
+    match line.split_once(' ') {
+        Some((prefix, message)) => match DateTime::parse_from_rfc3339(prefix) {
+            Ok(timestamp) => (Some(timestamp.with_timezone(&Utc)), message),
+            Err(_) => (None, line),
+        },
+        None => match DateTime::parse_from_rfc3339(line) {
+            Ok(timestamp) => (Some(timestamp.with_timezone(&Utc)), ""),
+            Err(_) => (None, line),
+        },
+    }
+}
+
+/// Where a reconnected log stream resumes, to skip the lines it repeats
+///
+/// `sinceTime` is inclusive, so a reconnect replays every line carrying the
+/// last timestamp sent. Several lines can share a timestamp, so exactly as
+/// many of those are skipped as were sent; a live stream is never filtered.
+#[derive(Default)]
+struct ResumePoint {
+    /// Timestamp of the last line sent
+    last: Option<DateTime<Utc>>,
+    /// Lines sent with exactly that timestamp
+    sent_at_last: usize,
+    /// Replayed lines with that timestamp still to skip, while reconnecting
+    replaying: Option<usize>,
+}
+
+impl ResumePoint {
+    fn keeps(&mut self, timestamp: Option<DateTime<Utc>>) -> bool {
+        let Some(timestamp) = timestamp else { return true };
+        if let (Some(to_skip), Some(last)) = (&mut self.replaying, self.last) {
+            if timestamp < last {
+                return false;
+            }
+            if timestamp == last && *to_skip > 0 {
+                *to_skip -= 1;
+                return false;
+            }
+            self.replaying = None;
+        }
+
+        if self.last == Some(timestamp) {
+            self.sent_at_last += 1;
+        } else {
+            self.last = Some(timestamp);
+            self.sent_at_last = 1;
+        }
+        true
+    }
+
+    /// Start skipping the replay, returning the time to resume from
+    fn reconnect(&mut self) -> Option<DateTime<Utc>> {
+        self.replaying = self.last.map(|_| self.sent_at_last);
+        self.last
+    }
+}
+
+/// Run the producers of a log stream until the frontend goes away
+///
+/// Waiting for the producers alone would keep an idle pod watch or log
+/// stream open, since they only notice the closed channel on their next send.
+async fn until_closed(producers: impl Future<Output = ()>, forward: impl Future<Output = ()>) {
+    let mut forward = std::pin::pin!(forward);
+    tokio::select! {
+        _ = &mut forward => {}
+        // Let everything they queued go out before finishing
+        _ = producers => forward.await,
+    }
+}
+
+/// Send lines in batches, and lifecycle events in order with them
+///
+/// Lines the filter drops never count towards a batch.
+/// At most `MAX_BATCH_LINES` go out per `FLUSH_INTERVAL`. When the readers
+/// produce faster than that the queue fills up and they stop reading, which
+/// holds back the log stream instead of flooding the UI or buffering without
+/// bound.
+async fn forward<F>(mut rx: mpsc::Receiver<Output>, filter: Option<LineFilter>, mut on_event: F)
+where
+    F: FnMut(LogEvent) -> bool,
+{
+    let mut lines = vec![];
+    let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+    flush.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            output = rx.recv(), if lines.len() < MAX_BATCH_LINES => match output {
+                Some(Output::Line(line)) => {
+                    if filter.as_ref().map_or(true, |f| f.keeps(&line.message)) {
+                        lines.push(line);
+                    }
+                }
+                Some(Output::Event(event)) => {
+                    if !send_lines(&mut lines, &mut on_event) || !on_event(event) {
+                        return;
+                    }
+                }
+                None => {
+                    send_lines(&mut lines, &mut on_event);
+                    return;
+                }
+            },
+            _ = flush.tick() => {
+                if !send_lines(&mut lines, &mut on_event) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn send_lines<F>(lines: &mut Vec<LogLine>, on_event: &mut F) -> bool
+where
+    F: FnMut(LogEvent) -> bool,
+{
+    if lines.is_empty() {
+        return true;
+    }
+    on_event(LogEvent::Lines {
+        lines: std::mem::take(lines),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_timestamps() {
+        let cases = [
+            ("2024-01-01T10:00:00.123456789Z hello world", Some("2024-01-01T10:00:00.123456789+00:00"), "hello world"),
+            ("2024-01-01T10:00:00Z ", Some("2024-01-01T10:00:00+00:00"), ""),
+            ("2024-01-01T10:00:00Z", Some("2024-01-01T10:00:00+00:00"), ""),
+            ("no timestamp here", None, "no timestamp here"),
+            ("", None, ""),
+        ];
+
+        for (line, timestamp, message) in cases {
+            let (parsed, rest) = split_timestamp(line);
+            assert_eq!(parsed.map(|t| t.to_rfc3339()).as_deref(), timestamp, "{:?}", line);
+            assert_eq!(rest, message, "{:?}", line);
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_idle_producers_once_forwarding_ends() {
+        let (producer_alive, producer_dropped) = tokio::sync::oneshot::channel::<()>();
+        let idle = async move {
+            let _alive = producer_alive;
+            std::future::pending::<()>().await
+        };
+
+        tokio::time::timeout(Duration::from_secs(1), until_closed(idle, async {}))
+            .await
+            .unwrap();
+        assert!(producer_dropped.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn forwards_everything_once_producers_end() {
+        let (tx, rx) = mpsc::channel(LINE_BUFFER);
+        let producer = async move {
+            let event = LogEvent::Ended {
+                pod: "web".to_string(),
+                container: "app".to_string(),
+                reason: "Pod deleted".to_string(),
+            };
+            tx.send(Output::Event(event)).await.unwrap();
+        };
+        let mut events = vec![];
+        until_closed(producer, forward(rx, None, |event| {
+            events.push(event);
+            true
+        }))
+        .await;
+        assert!(matches!(events.as_slice(), [LogEvent::Ended { .. }]));
+    }
+
+    #[test]
+    fn skips_only_replayed_lines() {
+        let lines = |resume: &mut ResumePoint, lines: &[&str]| -> Vec<String> {
+            lines
+                .iter()
+                .filter(|line| resume.keeps(split_timestamp(line).0))
+                .map(|line| split_timestamp(line).1.to_string())
+                .collect()
+        };
+        let mut resume = ResumePoint::default();
+
+        // Lines sharing a timestamp within one stream are all kept
+        let first = lines(
+            &mut resume,
+            &["2024-01-01T10:00:00Z a", "2024-01-01T10:00:01Z b", "2024-01-01T10:00:01Z c", "plain"],
+        );
+        assert_eq!(first, ["a", "b", "c", "plain"]);
+
+        // The reconnect replays from 10:00:01 inclusive, plus a line written meanwhile
+        assert_eq!(resume.reconnect().map(|t| t.to_rfc3339()).as_deref(), Some("2024-01-01T10:00:01+00:00"));
+        let second = lines(
+            &mut resume,
+            &[
+                "2024-01-01T10:00:00Z a",
+                "2024-01-01T10:00:01Z b",
+                "2024-01-01T10:00:01Z c",
+                "2024-01-01T10:00:01Z d",
+                "2024-01-01T10:00:02Z e",
+                "2024-01-01T10:00:02Z f",
+            ],
+        );
+        assert_eq!(second, ["d", "e", "f"]);
+
+        // Nothing sent yet: nothing to skip
+        let mut fresh = ResumePoint::default();
+        assert_eq!(fresh.reconnect(), None);
+        assert_eq!(lines(&mut fresh, &["2024-01-01T10:00:00Z a"]), ["a"]);
+    }
+}
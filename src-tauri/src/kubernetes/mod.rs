@@ -1,6 +1,23 @@
 pub mod client;
 pub mod cluster;
+pub mod nodes;
+pub mod drain;
 pub mod workloads;
+pub mod scale;
+pub mod rollout;
+pub mod delete;
+pub mod listing;
+pub mod pod_status;
 pub mod resources;
+pub mod subscriptions;
 pub mod discovery;
+pub mod kubeconfig;
+pub mod provider;
+pub mod platform;
+pub mod probe;
 pub mod exec;
+pub mod logs;
+pub mod diagnostics;
+pub mod pool;
+pub mod quantity;
+pub mod watcher;
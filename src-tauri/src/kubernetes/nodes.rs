@@ -0,0 +1,155 @@
+use anyhow::Result;
+use k8s_openapi::api::core::v1::{Node, Pod};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use kube::api::{ListParams, Patch, PatchParams};
+use kube::Api;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::kubernetes::quantity;
+use crate::types::{NodeCondition, NodeDetails, NodeResources, NodeTaint};
+
+const ROLE_LABEL_PREFIX: &str = "node-role.kubernetes.io/";
+
+/// List nodes with their capacity, placement, taints, conditions and pod counts
+pub async fn get_nodes(client: kube::Client) -> Result<Vec<NodeDetails>> {
+    let nodes: Api<Node> = Api::all(client.clone());
+    let pods: Api<Pod> = Api::all(client);
+
+    let params = ListParams::default();
+    let (node_list, pod_counts) = tokio::join!(nodes.list(&params), count_pods(pods));
+    let pod_counts = pod_counts
+        .inspect_err(|e| log::info!("Could not count pods per node: {}", e))
+        .ok();
+
+    let mut items: Vec<NodeDetails> = node_list?
+        .items
+        .into_iter()
+        .map(|node| {
+            let name = node.metadata.name.clone().unwrap_or_default();
+            let pod_count = pod_counts
+                .as_ref()
+                .map(|counts| counts.get(&name).copied().unwrap_or_default());
+            node_details(node, pod_count)
+        })
+        .collect();
+
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(items)
+}
+
+/// Mark a node unschedulable (cordon) or schedulable again (uncordon)
+pub async fn set_unschedulable(client: kube::Client, node_name: &str, unschedulable: bool) -> Result<()> {
+    let nodes: Api<Node> = Api::all(client);
+    let patch = serde_json::json!({ "spec": { "unschedulable": unschedulable } });
+    nodes
+        .patch(node_name, &PatchParams::default(), &Patch::Merge(&patch))
+        .await?;
+    Ok(())
+}
+
+/// Count non-terminated pods per node, like `kubectl describe node`
+async fn count_pods(pods: Api<Pod>) -> Result<HashMap<String, usize>, kube::Error> {
+    let params = ListParams::default().fields("status.phase!=Succeeded,status.phase!=Failed");
+
+    let mut counts = HashMap::new();
+    for pod in pods.list(&params).await?.items {
+        if let Some(node_name) = pod.spec.and_then(|s| s.node_name) {
+            *counts.entry(node_name).or_default() += 1;
+        }
+    }
+    Ok(counts)
+}
+
+fn node_details(node: Node, pod_count: Option<usize>) -> NodeDetails {
+    let labels = node.metadata.labels.unwrap_or_default();
+    let label = |keys: &[&str]| keys.iter().find_map(|k| labels.get(*k).cloned());
+
+    let spec = node.spec.unwrap_or_default();
+    let status = node.status.unwrap_or_default();
+
+    let addresses = status.addresses.unwrap_or_default();
+    let addresses_of = |kind: &str| {
+        addresses
+            .iter()
+            .filter(|a| a.type_ == kind)
+            .map(|a| a.address.clone())
+            .collect::<Vec<_>>()
+    };
+
+    NodeDetails {
+        roles: roles(&labels),
+        internal_ips: addresses_of("InternalIP"),
+        external_ips: addresses_of("ExternalIP"),
+        instance_type: label(&["node.kubernetes.io/instance-type", "beta.kubernetes.io/instance-type"]),
+        zone: label(&["topology.kubernetes.io/zone", "failure-domain.beta.kubernetes.io/zone"]),
+        region: label(&["topology.kubernetes.io/region", "failure-domain.beta.kubernetes.io/region"]),
+        kubelet_version: status.node_info.map(|i| i.kubelet_version).unwrap_or_default(),
+        capacity: resources(status.capacity.as_ref()),
+        allocatable: resources(status.allocatable.as_ref()),
+        taints: spec
+            .taints
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| NodeTaint {
+                key: t.key,
+                value: t.value,
+                effect: t.effect,
+            })
+            .collect(),
+        conditions: status
+            .conditions
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| NodeCondition {
+                type_: c.type_,
+                status: c.status,
+                reason: c.reason,
+                message: c.message,
+                last_transition_time: c.last_transition_time.map(|t| t.0.to_rfc3339()),
+            })
+            .collect(),
+        unschedulable: spec.unschedulable.unwrap_or(false),
+        pod_count,
+        creation_timestamp: node
+            .metadata
+            .creation_timestamp
+            .map(|ts| ts.0.to_rfc3339())
+            .unwrap_or_default(),
+        name: node.metadata.name.unwrap_or_default(),
+    }
+}
+
+/// Roles from `node-role.kubernetes.io/<role>` labels and the legacy `kubernetes.io/role`
+fn roles(labels: &BTreeMap<String, String>) -> Vec<String> {
+    let mut roles: Vec<String> = labels
+        .keys()
+        .filter_map(|k| k.strip_prefix(ROLE_LABEL_PREFIX))
+        .filter(|role| !role.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if let Some(role) = labels.get("kubernetes.io/role") {
+        if !roles.contains(role) {
+            roles.push(role.clone());
+        }
+    }
+
+    roles.sort();
+    roles
+}
+
+fn resources(quantities: Option<&BTreeMap<String, Quantity>>) -> NodeResources {
+    let get = |name: &str| {
+        quantities
+            .and_then(|q| q.get(name))
+            .and_then(quantity::parse)
+            .unwrap_or_default()
+    };
+
+    NodeResources {
+        cpu: get("cpu"),
+        memory: get("memory"),
+        pods: get("pods"),
+        ephemeral_storage: get("ephemeral-storage"),
+    }
+}
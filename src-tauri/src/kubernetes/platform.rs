@@ -0,0 +1,138 @@
+use k8s_openapi::api::core::v1::Node;
+use k8s_openapi::apimachinery::pkg::version::Info;
+use std::collections::BTreeMap;
+
+use crate::types::{Distribution, NodePlatform, PlatformInfo};
+
+/// Kubernetes minor version of the `k8s-openapi` feature the app is compiled with (`v1_30`)
+const COMPILED_MINOR: u32 = 30;
+
+/// How many minor versions the server may differ by, matching kubectl's skew policy
+const SUPPORTED_SKEW: u32 = 1;
+
+/// Summarise the server version and the software running on the nodes
+pub fn describe(version: Option<&Info>, nodes: &[Node]) -> PlatformInfo {
+    let node_infos: Vec<_> = nodes
+        .iter()
+        .filter_map(|n| n.status.as_ref().and_then(|s| s.node_info.as_ref()))
+        .collect();
+
+    let mut kubelet_versions: Vec<String> = node_infos.iter().map(|i| i.kubelet_version.clone()).collect();
+    // Break ties like `v1.30.1` / `v1.30.1+k3s1` by the string so duplicates end up adjacent
+    kubelet_versions.sort_by_key(|v| (parse_version(v), v.clone()));
+    kubelet_versions.dedup();
+
+    let mut container_runtimes: Vec<String> = node_infos
+        .iter()
+        .map(|i| i.container_runtime_version.clone())
+        .collect();
+    container_runtimes.sort();
+    container_runtimes.dedup();
+
+    let mut platforms: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for info in &node_infos {
+        *platforms
+            .entry((info.operating_system.clone(), info.architecture.clone()))
+            .or_default() += 1;
+    }
+    let node_platforms = platforms
+        .into_iter()
+        .map(|((os, architecture), count)| NodePlatform { os, architecture, count })
+        .collect();
+
+    PlatformInfo {
+        server_version: version.map(|v| v.git_version.clone()),
+        server_platform: version.map(|v| v.platform.clone()),
+        kubelet_versions,
+        container_runtimes,
+        node_platforms,
+        distribution: Some(detect_distribution(version, nodes)),
+        compatibility_warning: version.and_then(compatibility_warning),
+    }
+}
+
+/// Detect the distribution from version suffixes, node provider ids and well-known labels
+fn detect_distribution(version: Option<&Info>, nodes: &[Node]) -> Distribution {
+    let git_version = version.map(|v| v.git_version.as_str()).unwrap_or_default();
+    let kubelet_has = |marker: &str| {
+        nodes.iter().any(|n| {
+            n.status
+                .as_ref()
+                .and_then(|s| s.node_info.as_ref())
+                .is_some_and(|i| i.kubelet_version.contains(marker))
+        })
+    };
+    let provider_id_has = |prefix: &str| {
+        nodes.iter().any(|n| {
+            n.spec
+                .as_ref()
+                .and_then(|s| s.provider_id.as_deref())
+                .is_some_and(|id| id.starts_with(prefix))
+        })
+    };
+    let label_has = |prefix: &str| {
+        nodes.iter().any(|n| {
+            n.metadata
+                .labels
+                .as_ref()
+                .is_some_and(|labels| labels.keys().any(|k| k.starts_with(prefix)))
+        })
+    };
+
+    if git_version.contains("-eks-") || label_has("eks.amazonaws.com/") {
+        Distribution::Eks
+    } else if git_version.contains("-gke.") || label_has("cloud.google.com/gke-") {
+        Distribution::Gke
+    } else if label_has("kubernetes.azure.com/") {
+        Distribution::Aks
+    } else if label_has("node.openshift.io/") {
+        Distribution::OpenShift
+    } else if git_version.contains("+k3s") || kubelet_has("+k3s") {
+        Distribution::K3s
+    } else if provider_id_has("kind://") {
+        Distribution::Kind
+    } else {
+        Distribution::Kubernetes
+    }
+}
+
+/// Warn when the server is too far from the API version the app was compiled against
+fn compatibility_warning(version: &Info) -> Option<String> {
+    // `minor` may carry a suffix such as "30+" on managed offerings
+    let minor: u32 = version.minor.trim_end_matches(|c: char| !c.is_ascii_digit()).parse().ok()?;
+    if version.major != "1" {
+        return Some(format!(
+            "Server version {} is not Kubernetes 1.x; this app is built against the 1.{} API",
+            version.git_version, COMPILED_MINOR
+        ));
+    }
+
+    if minor > COMPILED_MINOR + SUPPORTED_SKEW {
+        Some(format!(
+            "Server version {} is newer than the Kubernetes 1.{} API this app is built against; newer fields and resources will not be shown",
+            version.git_version, COMPILED_MINOR
+        ))
+    } else if minor + SUPPORTED_SKEW < COMPILED_MINOR {
+        Some(format!(
+            "Server version {} is older than the Kubernetes 1.{} API this app is built against; some resources may be unavailable",
+            version.git_version, COMPILED_MINOR
+        ))
+    } else {
+        None
+    }
+}
+
+/// Numeric `(major, minor, patch)` of a version such as `v1.30.2-eks-1234`
+fn parse_version(version: &str) -> (u32, u32, u32) {
+    let core = version
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default();
+    let mut parts = core.split('.').map(|p| p.parse().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
@@ -0,0 +1,565 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use k8s_openapi::api::core::v1::{Container, ContainerStateTerminated, ContainerStatus, Pod};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+/// Reason the node controller sets on pods of a node that stopped responding
+const NODE_UNREACHABLE_REASON: &str = "NodeLost";
+
+/// Pod status as `kubectl get pods` prints it
+#[derive(Debug, Default)]
+pub struct PodSummary {
+    /// STATUS column: phase, container reason, `Init:x/y`, `Terminating`, ...
+    pub status: String,
+    pub ready_containers: usize,
+    pub total_containers: usize,
+    pub restarts: i32,
+    pub last_restart: Option<DateTime<Utc>>,
+    /// Most recent termination of any container
+    pub last_termination: Option<ContainerStateTerminated>,
+}
+
+/// Work out a pod's status the way `kubectl get pods` does
+///
+/// Follows kubectl's `printPod`: init container progress takes precedence, then
+/// the waiting or terminated reason of the containers, and a deletion timestamp
+/// turns a live pod into `Terminating`. Sidecars (init containers with
+/// `restartPolicy: Always`) count towards readiness like regular containers.
+pub fn summarize(pod: &Pod) -> PodSummary {
+    let spec = pod.spec.as_ref();
+    let status = pod.status.clone().unwrap_or_default();
+    let init_specs: &[Container] = spec.and_then(|s| s.init_containers.as_deref()).unwrap_or_default();
+    let is_sidecar = |name: &str| {
+        init_specs
+            .iter()
+            .any(|c| c.name == name && c.restart_policy.as_deref() == Some("Always"))
+    };
+
+    let mut summary = PodSummary {
+        status: status.reason.clone().or(status.phase.clone()).unwrap_or_else(|| "Unknown".to_string()),
+        total_containers: spec.map(|s| s.containers.len()).unwrap_or_default()
+            + init_specs.iter().filter(|c| is_sidecar(&c.name)).count(),
+        ..Default::default()
+    };
+
+    let scheduling_gated = status.conditions.iter().flatten().any(|c| {
+        c.type_ == "PodScheduled" && c.status == "False" && c.reason.as_deref() == Some("SchedulingGated")
+    });
+    if scheduling_gated {
+        summary.status = "SchedulingGated".to_string();
+    }
+
+    let init_statuses = status.init_container_statuses.as_deref().unwrap_or_default();
+    let container_statuses = status.container_statuses.as_deref().unwrap_or_default();
+    for container in init_statuses.iter().chain(container_statuses) {
+        if let Some(terminated) = container.last_state.as_ref().and_then(|s| s.terminated.as_ref()) {
+            let finished = terminated.finished_at.as_ref().map(|t| t.0);
+            let latest = summary
+                .last_termination
+                .as_ref()
+                .and_then(|t| t.finished_at.as_ref())
+                .map(|t| t.0);
+            if summary.last_termination.is_none() || finished > latest {
+                summary.last_termination = Some(terminated.clone());
+            }
+        }
+    }
+
+    let mut restarts = 0;
+    let mut last_restart = None;
+    let mut sidecar_restarts = 0;
+    let mut last_sidecar_restart = None;
+    let mut initializing = false;
+
+    for (i, container) in init_statuses.iter().enumerate() {
+        restarts += container.restart_count;
+        last_restart = last_restart.max(last_finished(container));
+        let sidecar = is_sidecar(&container.name);
+        if sidecar {
+            sidecar_restarts += container.restart_count;
+            last_sidecar_restart = last_sidecar_restart.max(last_finished(container));
+        }
+
+        let state = container.state.clone().unwrap_or_default();
+        if state.terminated.as_ref().is_some_and(|t| t.exit_code == 0) {
+            continue;
+        }
+        if sidecar && container.started == Some(true) {
+            if container.ready {
+                summary.ready_containers += 1;
+            }
+            continue;
+        }
+
+        summary.status = if let Some(terminated) = &state.terminated {
+            match terminated.reason.as_deref() {
+                Some(reason) if !reason.is_empty() => format!("Init:{}", reason),
+                _ => match terminated.signal {
+                    Some(signal) if signal != 0 => format!("Init:Signal:{}", signal),
+                    _ => format!("Init:ExitCode:{}", terminated.exit_code),
+                },
+            }
+        } else if let Some(reason) = state
+            .waiting
+            .as_ref()
+            .and_then(|w| w.reason.as_deref())
+            .filter(|r| !r.is_empty() && *r != "PodInitializing")
+        {
+            format!("Init:{}", reason)
+        } else {
+            format!("Init:{}/{}", i, init_specs.len())
+        };
+        initializing = true;
+        break;
+    }
+
+    let initialized = status
+        .conditions
+        .iter()
+        .flatten()
+        .any(|c| c.type_ == "Initialized" && c.status == "True");
+
+    if !initializing || initialized {
+        restarts = sidecar_restarts;
+        last_restart = last_sidecar_restart;
+        let mut has_running = false;
+
+        for container in container_statuses.iter().rev() {
+            restarts += container.restart_count;
+            last_restart = last_restart.max(last_finished(container));
+
+            let state = container.state.clone().unwrap_or_default();
+            let waiting_reason = state.waiting.as_ref().and_then(|w| w.reason.clone()).filter(|r| !r.is_empty());
+            if let Some(reason) = waiting_reason {
+                summary.status = reason;
+            } else if let Some(terminated) = &state.terminated {
+                summary.status = match terminated.reason.as_deref() {
+                    Some(reason) if !reason.is_empty() => reason.to_string(),
+                    _ => match terminated.signal {
+                        Some(signal) if signal != 0 => format!("Signal:{}", signal),
+                        _ => format!("ExitCode:{}", terminated.exit_code),
+                    },
+                };
+            } else if container.ready && state.running.is_some() {
+                has_running = true;
+                summary.ready_containers += 1;
+            }
+        }
+
+        // A pod with one finished and one running container is still running
+        if summary.status == "Completed" && has_running {
+            let ready = status
+                .conditions
+                .iter()
+                .flatten()
+                .any(|c| c.type_ == "Ready" && c.status == "True");
+            summary.status = if ready { "Running" } else { "NotReady" }.to_string();
+        }
+    }
+
+    if pod.metadata.deletion_timestamp.is_some() {
+        let terminal = matches!(status.phase.as_deref(), Some("Succeeded") | Some("Failed"));
+        if status.reason.as_deref() == Some(NODE_UNREACHABLE_REASON) {
+            summary.status = "Unknown".to_string();
+        } else if !terminal {
+            summary.status = "Terminating".to_string();
+        }
+    }
+
+    summary.restarts = restarts;
+    summary.last_restart = last_restart;
+    summary
+}
+
+/// When a container last terminated before its current run
+fn last_finished(container: &ContainerStatus) -> Option<DateTime<Utc>> {
+    container
+        .last_state
+        .as_ref()
+        .and_then(|s| s.terminated.as_ref())
+        .and_then(|t| t.finished_at.as_ref())
+        .map(|t| t.0)
+}
+
+/// Time since a timestamp, formatted like the AGE column of kubectl
+pub fn age(time: Option<&Time>) -> String {
+    match time {
+        Some(time) => human_duration(Utc::now().signed_duration_since(time.0)),
+        None => "<unknown>".to_string(),
+    }
+}
+
+/// Format a duration like kubectl: `45s`, `3m12s`, `5h20m`, `3d4h`, `2y30d`
+pub fn human_duration(duration: TimeDelta) -> String {
+    let seconds = duration.num_seconds();
+    if seconds < -1 {
+        return "<invalid>".to_string();
+    } else if seconds < 0 {
+        return "0s".to_string();
+    } else if seconds < 60 * 2 {
+        return format!("{}s", seconds);
+    }
+
+    let minutes = duration.num_minutes();
+    if minutes < 10 {
+        return with_remainder(minutes, "m", seconds % 60, "s");
+    } else if minutes < 60 * 3 {
+        return format!("{}m", minutes);
+    }
+
+    let hours = duration.num_hours();
+    if hours < 8 {
+        with_remainder(hours, "h", minutes % 60, "m")
+    } else if hours < 48 {
+        format!("{}h", hours)
+    } else if hours < 24 * 8 {
+        with_remainder(hours / 24, "d", hours % 24, "h")
+    } else if hours < 24 * 365 * 2 {
+        format!("{}d", hours / 24)
+    } else if hours < 24 * 365 * 8 {
+        with_remainder(hours / 24 / 365, "y", (hours / 24) % 365, "d")
+    } else {
+        format!("{}y", hours / 24 / 365)
+    }
+}
+
+fn with_remainder(value: i64, unit: &str, remainder: i64, remainder_unit: &str) -> String {
+    if remainder == 0 {
+        format!("{}{}", value, unit)
+    } else {
+        format!("{}{}{}{}", value, unit, remainder, remainder_unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pod(value: serde_json::Value) -> Pod {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn running(name: &str, ready: bool) -> serde_json::Value {
+        json!({ "name": name, "ready": ready, "restartCount": 0, "image": "", "imageID": "", "state": { "running": {} } })
+    }
+
+    fn waiting(name: &str, reason: &str, restarts: i32) -> serde_json::Value {
+        json!({ "name": name, "ready": false, "restartCount": restarts, "image": "", "imageID": "", "state": { "waiting": { "reason": reason } } })
+    }
+
+    fn terminated(name: &str, terminated: serde_json::Value) -> serde_json::Value {
+        json!({ "name": name, "ready": false, "restartCount": 0, "image": "", "imageID": "", "state": { "terminated": terminated } })
+    }
+
+    fn containers(names: &[&str]) -> serde_json::Value {
+        names.iter().map(|n| json!({ "name": n })).collect()
+    }
+
+    #[test]
+    fn status_column_matches_kubectl() {
+        let cases = [
+            (
+                "running and ready",
+                pod(json!({
+                    "spec": { "containers": containers(&["app", "proxy"]) },
+                    "status": { "phase": "Running", "containerStatuses": [running("app", true), running("proxy", true)] }
+                })),
+                "Running",
+                2,
+                2,
+            ),
+            (
+                "one container not ready",
+                pod(json!({
+                    "spec": { "containers": containers(&["app", "proxy"]) },
+                    "status": { "phase": "Running", "containerStatuses": [running("app", true), running("proxy", false)] }
+                })),
+                "Running",
+                1,
+                2,
+            ),
+            (
+                "waiting reason wins",
+                pod(json!({
+                    "spec": { "containers": containers(&["app"]) },
+                    "status": { "phase": "Running", "containerStatuses": [waiting("app", "CrashLoopBackOff", 5)] }
+                })),
+                "CrashLoopBackOff",
+                0,
+                1,
+            ),
+            (
+                "terminated reason",
+                pod(json!({
+                    "spec": { "containers": containers(&["app"]) },
+                    "status": { "phase": "Failed", "containerStatuses": [terminated("app", json!({ "exitCode": 1, "reason": "Error" }))] }
+                })),
+                "Error",
+                0,
+                1,
+            ),
+            (
+                "terminated by signal",
+                pod(json!({
+                    "spec": { "containers": containers(&["app"]) },
+                    "status": { "phase": "Failed", "containerStatuses": [terminated("app", json!({ "exitCode": 137, "signal": 9 }))] }
+                })),
+                "Signal:9",
+                0,
+                1,
+            ),
+            (
+                "terminated with exit code only",
+                pod(json!({
+                    "spec": { "containers": containers(&["app"]) },
+                    "status": { "phase": "Failed", "containerStatuses": [terminated("app", json!({ "exitCode": 3 }))] }
+                })),
+                "ExitCode:3",
+                0,
+                1,
+            ),
+            (
+                "pod reason such as Evicted",
+                pod(json!({
+                    "spec": { "containers": containers(&["app"]) },
+                    "status": { "phase": "Failed", "reason": "Evicted" }
+                })),
+                "Evicted",
+                0,
+                1,
+            ),
+            (
+                "init containers in progress",
+                pod(json!({
+                    "spec": { "initContainers": containers(&["migrate", "seed"]), "containers": containers(&["app"]) },
+                    "status": {
+                        "phase": "Pending",
+                        "initContainerStatuses": [
+                            terminated("migrate", json!({ "exitCode": 0, "reason": "Completed" })),
+                            running("seed", false)
+                        ],
+                        "containerStatuses": [waiting("app", "PodInitializing", 0)]
+                    }
+                })),
+                "Init:1/2",
+                0,
+                1,
+            ),
+            (
+                "init container PodInitializing is not a reason",
+                pod(json!({
+                    "spec": { "initContainers": containers(&["migrate"]), "containers": containers(&["app"]) },
+                    "status": { "phase": "Pending", "initContainerStatuses": [waiting("migrate", "PodInitializing", 0)] }
+                })),
+                "Init:0/1",
+                0,
+                1,
+            ),
+            (
+                "init container waiting",
+                pod(json!({
+                    "spec": { "initContainers": containers(&["migrate"]), "containers": containers(&["app"]) },
+                    "status": { "phase": "Pending", "initContainerStatuses": [waiting("migrate", "ImagePullBackOff", 0)] }
+                })),
+                "Init:ImagePullBackOff",
+                0,
+                1,
+            ),
+            (
+                "init container failed with reason",
+                pod(json!({
+                    "spec": { "initContainers": containers(&["migrate"]), "containers": containers(&["app"]) },
+                    "status": { "phase": "Pending", "initContainerStatuses": [terminated("migrate", json!({ "exitCode": 1, "reason": "Error" }))] }
+                })),
+                "Init:Error",
+                0,
+                1,
+            ),
+            (
+                "init container failed with exit code",
+                pod(json!({
+                    "spec": { "initContainers": containers(&["migrate"]), "containers": containers(&["app"]) },
+                    "status": { "phase": "Pending", "initContainerStatuses": [terminated("migrate", json!({ "exitCode": 2 }))] }
+                })),
+                "Init:ExitCode:2",
+                0,
+                1,
+            ),
+            (
+                "init container killed by signal",
+                pod(json!({
+                    "spec": { "initContainers": containers(&["migrate"]), "containers": containers(&["app"]) },
+                    "status": { "phase": "Pending", "initContainerStatuses": [terminated("migrate", json!({ "exitCode": 137, "signal": 9 }))] }
+                })),
+                "Init:Signal:9",
+                0,
+                1,
+            ),
+            (
+                "started sidecar counts as a container",
+                pod(json!({
+                    "spec": {
+                        "initContainers": [{ "name": "envoy", "restartPolicy": "Always" }],
+                        "containers": containers(&["app"])
+                    },
+                    "status": {
+                        "phase": "Running",
+                        "conditions": [{ "type": "Initialized", "status": "True" }],
+                        "initContainerStatuses": [{
+                            "name": "envoy", "ready": true, "started": true, "restartCount": 0, "image": "", "imageID": "",
+                            "state": { "running": {} }
+                        }],
+                        "containerStatuses": [running("app", true)]
+                    }
+                })),
+                "Running",
+                2,
+                2,
+            ),
+            (
+                "completed container next to a ready one",
+                pod(json!({
+                    "spec": { "containers": containers(&["app", "job"]) },
+                    "status": {
+                        "phase": "Running",
+                        "conditions": [{ "type": "Ready", "status": "True" }],
+                        "containerStatuses": [running("app", true), terminated("job", json!({ "exitCode": 0, "reason": "Completed" }))]
+                    }
+                })),
+                "Running",
+                1,
+                2,
+            ),
+            (
+                "completed container next to an unready one",
+                pod(json!({
+                    "spec": { "containers": containers(&["app", "job"]) },
+                    "status": {
+                        "phase": "Running",
+                        "conditions": [{ "type": "Ready", "status": "False" }],
+                        "containerStatuses": [running("app", true), terminated("job", json!({ "exitCode": 0, "reason": "Completed" }))]
+                    }
+                })),
+                "NotReady",
+                1,
+                2,
+            ),
+            (
+                "scheduling gated",
+                pod(json!({
+                    "spec": { "containers": containers(&["app"]) },
+                    "status": {
+                        "phase": "Pending",
+                        "conditions": [{ "type": "PodScheduled", "status": "False", "reason": "SchedulingGated" }]
+                    }
+                })),
+                "SchedulingGated",
+                0,
+                1,
+            ),
+            (
+                "deleted while running",
+                pod(json!({
+                    "metadata": { "deletionTimestamp": "2024-01-01T00:00:00Z" },
+                    "spec": { "containers": containers(&["app"]) },
+                    "status": { "phase": "Running", "containerStatuses": [running("app", true)] }
+                })),
+                "Terminating",
+                1,
+                1,
+            ),
+            (
+                "deleted after finishing keeps its status",
+                pod(json!({
+                    "metadata": { "deletionTimestamp": "2024-01-01T00:00:00Z" },
+                    "spec": { "containers": containers(&["app"]) },
+                    "status": { "phase": "Succeeded", "containerStatuses": [terminated("app", json!({ "exitCode": 0, "reason": "Completed" }))] }
+                })),
+                "Completed",
+                0,
+                1,
+            ),
+            (
+                "deleted on a lost node",
+                pod(json!({
+                    "metadata": { "deletionTimestamp": "2024-01-01T00:00:00Z" },
+                    "spec": { "containers": containers(&["app"]) },
+                    "status": { "phase": "Running", "reason": "NodeLost", "containerStatuses": [running("app", true)] }
+                })),
+                "Unknown",
+                1,
+                1,
+            ),
+        ];
+
+        for (name, pod, status, ready, total) in cases {
+            let summary = summarize(&pod);
+            assert_eq!(summary.status, status, "{}", name);
+            assert_eq!(
+                (summary.ready_containers, summary.total_containers),
+                (ready, total),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn restarts_and_last_termination() {
+        let pod = pod(json!({
+            "spec": { "containers": containers(&["app", "proxy"]) },
+            "status": {
+                "phase": "Running",
+                "containerStatuses": [
+                    {
+                        "name": "app", "ready": true, "restartCount": 3, "image": "", "imageID": "",
+                        "state": { "running": {} },
+                        "lastState": { "terminated": { "exitCode": 137, "reason": "OOMKilled", "finishedAt": "2024-01-01T10:00:00Z" } }
+                    },
+                    {
+                        "name": "proxy", "ready": true, "restartCount": 1, "image": "", "imageID": "",
+                        "state": { "running": {} },
+                        "lastState": { "terminated": { "exitCode": 1, "reason": "Error", "finishedAt": "2024-01-01T09:00:00Z" } }
+                    }
+                ]
+            }
+        }));
+
+        let summary = summarize(&pod);
+        assert_eq!(summary.restarts, 4);
+        assert_eq!(summary.last_restart.map(|t| t.to_rfc3339()).as_deref(), Some("2024-01-01T10:00:00+00:00"));
+        assert_eq!(summary.last_termination.and_then(|t| t.reason).as_deref(), Some("OOMKilled"));
+    }
+
+    #[test]
+    fn human_duration_buckets() {
+        let cases = [
+            (-2, "<invalid>"),
+            (-1, "0s"),
+            (0, "0s"),
+            (119, "119s"),
+            (120, "2m"),
+            (121, "2m1s"),
+            (599, "9m59s"),
+            (600, "10m"),
+            (3 * 3600 - 60, "179m"),
+            (3 * 3600, "3h"),
+            (3 * 3600 + 300, "3h5m"),
+            (8 * 3600 - 60, "7h59m"),
+            (8 * 3600, "8h"),
+            (48 * 3600 - 3600, "47h"),
+            (48 * 3600, "2d"),
+            (53 * 3600, "2d5h"),
+            (8 * 86400 - 3600, "7d23h"),
+            (8 * 86400, "8d"),
+            (729 * 86400, "729d"),
+            (730 * 86400, "2y"),
+            (760 * 86400, "2y30d"),
+            (8 * 365 * 86400, "8y"),
+        ];
+
+        for (seconds, expected) in cases {
+            assert_eq!(human_duration(TimeDelta::seconds(seconds)), expected, "{}s", seconds);
+        }
+    }
+}
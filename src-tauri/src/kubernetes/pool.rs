@@ -0,0 +1,83 @@
+use anyhow::Result;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use crate::kubernetes::client;
+use crate::types::ClusterContext;
+
+/// A cached client together with the fingerprint of the kubeconfig it was built from
+struct PooledClient {
+    fingerprint: u64,
+    from_default_kubeconfig: bool,
+    client: kube::Client,
+}
+
+/// Registry of live Kubernetes clients, keyed by cluster id
+///
+/// Building a client re-parses the kubeconfig, runs any exec auth plugin and
+/// performs a fresh TLS handshake, so commands share one client per cluster.
+/// A client is rebuilt when the kubeconfig it was created from changes.
+#[derive(Default)]
+pub struct ClientPool {
+    clients: Mutex<HashMap<String, PooledClient>>,
+}
+
+impl ClientPool {
+    /// Get the cached client for a cluster, building it on first use
+    pub async fn get(&self, config: &ClusterContext) -> Result<kube::Client> {
+        let fingerprint = fingerprint(config);
+
+        if let Some(pooled) = self.clients.lock().unwrap().get(&config.id) {
+            if pooled.fingerprint == fingerprint {
+                return Ok(pooled.client.clone());
+            }
+        }
+
+        // Build outside the lock so a slow auth plugin doesn't block other clusters
+        let client = client::from_cluster_context(config).await?;
+
+        self.clients.lock().unwrap().insert(
+            config.id.clone(),
+            PooledClient {
+                fingerprint,
+                from_default_kubeconfig: config.kubeconfig.is_none(),
+                client: client.clone(),
+            },
+        );
+
+        Ok(client)
+    }
+
+    /// Drop the cached client for a cluster
+    pub fn remove(&self, cluster_id: &str) {
+        self.clients.lock().unwrap().remove(cluster_id);
+    }
+
+    /// Drop cached clients built from the default kubeconfig after it changes on disk
+    pub fn clear_default_kubeconfig_clients(&self) {
+        self.clients
+            .lock()
+            .unwrap()
+            .retain(|_, pooled| !pooled.from_default_kubeconfig);
+    }
+
+    /// Drop cached clients for clusters that are no longer configured
+    pub fn retain(&self, clusters: &[ClusterContext]) {
+        self.clients
+            .lock()
+            .unwrap()
+            .retain(|id, _| clusters.iter().any(|c| &c.id == id));
+    }
+}
+
+/// Hash the parts of a cluster context that affect how its client is built
+fn fingerprint(config: &ClusterContext) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    config.kubeconfig.hash(&mut hasher);
+    config.context_name.hash(&mut hasher);
+    config.cluster_name.hash(&mut hasher);
+    config.user_name.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,136 @@
+use kube::config::{KubeConfigOptions, Kubeconfig};
+use kube::Config;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::error::AppError;
+use crate::kubernetes::discovery;
+use crate::types::{AuthOutcome, ClusterProbe};
+
+/// Default time allowed for each context before it is reported unreachable
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Contexts probed at once, so exec plugins don't all spawn at the same time
+const MAX_CONCURRENT_PROBES: usize = 8;
+
+/// Probe every discovered context in parallel, reporting each result as it completes
+pub async fn probe_clusters<F>(
+    extra_paths: &[String],
+    timeout: Option<Duration>,
+    mut on_result: F,
+) -> Result<Vec<ClusterProbe>, AppError>
+where
+    F: FnMut(&ClusterProbe),
+{
+    let (kubeconfig, _) = discovery::load_kubeconfig(extra_paths)?;
+    let kubeconfig = Arc::new(kubeconfig);
+    let timeout = timeout.unwrap_or(DEFAULT_TIMEOUT);
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_PROBES));
+
+    let mut probes = JoinSet::new();
+    for context in &kubeconfig.contexts {
+        let context_name = context.name.clone();
+        let kubeconfig = kubeconfig.clone();
+        let permits = permits.clone();
+
+        probes.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let started = Instant::now();
+            let result = tokio::time::timeout(timeout, probe_context(&kubeconfig, &context_name)).await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+
+            match result {
+                Ok(probe) => ClusterProbe { latency_ms, ..probe },
+                Err(_) => ClusterProbe {
+                    context_name,
+                    reachable: false,
+                    server_version: None,
+                    auth: AuthOutcome::NotChecked,
+                    latency_ms,
+                    error: Some(AppError::Timeout {
+                        message: format!("No response within {}s", timeout.as_secs()),
+                    }),
+                },
+            }
+        });
+    }
+
+    let mut results = Vec::with_capacity(probes.len());
+    while let Some(joined) = probes.join_next().await {
+        match joined {
+            Ok(probe) => {
+                on_result(&probe);
+                results.push(probe);
+            }
+            Err(e) => log::warn!("Cluster probe task failed: {}", e),
+        }
+    }
+
+    Ok(results)
+}
+
+/// Check that a context's API server answers and accepts its credentials
+async fn probe_context(kubeconfig: &Kubeconfig, context_name: &str) -> ClusterProbe {
+    let mut probe = ClusterProbe {
+        context_name: context_name.to_string(),
+        reachable: false,
+        server_version: None,
+        auth: AuthOutcome::NotChecked,
+        latency_ms: 0,
+        error: None,
+    };
+
+    let options = KubeConfigOptions {
+        context: Some(context_name.to_string()),
+        ..Default::default()
+    };
+    let client = match Config::from_custom_kubeconfig(kubeconfig.clone(), &options).await {
+        Ok(config) => kube::Client::try_from(config).map_err(AppError::from),
+        Err(e) => Err(AppError::from(anyhow::Error::from(e))),
+    };
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => {
+            probe.error = Some(e);
+            return probe;
+        }
+    };
+
+    // /version is readable anonymously on most clusters, so it only proves reachability
+    match client.apiserver_version().await {
+        Ok(info) => {
+            probe.reachable = true;
+            probe.server_version = Some(info.git_version);
+        }
+        Err(e) => {
+            let error = AppError::from(e);
+            // An auth plugin failure happens before the request is sent
+            if matches!(error, AppError::AuthPluginFailed { .. }) {
+                probe.auth = AuthOutcome::PluginFailed;
+            } else if matches!(error, AppError::Unauthorized { .. } | AppError::Forbidden { .. }) {
+                probe.reachable = true;
+                probe.auth = AuthOutcome::Rejected;
+            }
+            probe.error = Some(error);
+            return probe;
+        }
+    }
+
+    // API discovery is only served to authenticated users
+    match client.list_core_api_versions().await {
+        Ok(_) => probe.auth = AuthOutcome::Authenticated,
+        Err(e) => {
+            let error = AppError::from(e);
+            probe.auth = match error {
+                AppError::AuthPluginFailed { .. } => AuthOutcome::PluginFailed,
+                AppError::Unauthorized { .. } | AppError::Forbidden { .. } => AuthOutcome::Rejected,
+                _ => AuthOutcome::NotChecked,
+            };
+            probe.error = Some(error);
+        }
+    }
+
+    probe
+}
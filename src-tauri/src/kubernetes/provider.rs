@@ -0,0 +1,347 @@
+use kube::config::ExecConfig;
+
+use crate::types::ProviderType;
+
+/// Provider, location and cloud identity inferred from a kubeconfig context
+#[derive(Debug, Default)]
+pub struct ProviderDetails {
+    pub provider: Option<ProviderType>,
+    pub region: Option<String>,
+    /// AWS account id or GCP project
+    pub account: Option<String>,
+    /// Cluster name as known to the provider, which often differs from the context name
+    pub cluster_name: Option<String>,
+}
+
+impl ProviderDetails {
+    fn provider(provider: ProviderType) -> Self {
+        ProviderDetails {
+            provider: Some(provider),
+            ..Default::default()
+        }
+    }
+
+    /// Fill fields that are still unknown from a less reliable source
+    fn or(self, other: ProviderDetails) -> Self {
+        ProviderDetails {
+            provider: self.provider.or(other.provider),
+            region: self.region.or(other.region),
+            account: self.account.or(other.account),
+            cluster_name: self.cluster_name.or(other.cluster_name),
+        }
+    }
+}
+
+/// Detect the provider of a context
+///
+/// The exec auth plugin is the most reliable signal, followed by the API server
+/// host, followed by the naming conventions provider CLIs use for contexts.
+/// `provider` is left empty when none of them match.
+pub fn detect(
+    context_name: &str,
+    cluster_name: &str,
+    server: &str,
+    exec: Option<&ExecConfig>,
+) -> ProviderDetails {
+    exec.map(from_exec).unwrap_or_default()
+        .or(from_server(server))
+        .or(from_name(context_name))
+        .or(from_name(cluster_name))
+}
+
+/// Detect the provider from the exec credential plugin
+fn from_exec(exec: &ExecConfig) -> ProviderDetails {
+    let command = exec.command.as_deref().unwrap_or_default();
+    let command = command
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(command)
+        .trim_end_matches(".exe");
+    let args = exec.args.as_deref().unwrap_or_default();
+
+    match command {
+        "aws" if args.iter().any(|a| a == "get-token") => ProviderDetails {
+            provider: Some(ProviderType::Aws),
+            region: arg_value(args, "--region").or_else(|| env_value(exec, "AWS_REGION")),
+            cluster_name: arg_value(args, "--cluster-name").or_else(|| arg_value(args, "--cluster-id")),
+            ..Default::default()
+        },
+        "aws-iam-authenticator" => ProviderDetails {
+            provider: Some(ProviderType::Aws),
+            region: env_value(exec, "AWS_REGION"),
+            cluster_name: arg_value(args, "-i").or_else(|| arg_value(args, "--cluster-id")),
+            ..Default::default()
+        },
+        "gke-gcloud-auth-plugin" | "gcloud" => ProviderDetails::provider(ProviderType::Gcp),
+        "kubelogin" => ProviderDetails::provider(ProviderType::Azure),
+        "doctl" => ProviderDetails::provider(ProviderType::DigitalOcean),
+        "oc" => ProviderDetails::provider(ProviderType::OpenShift),
+        "rancher" => ProviderDetails::provider(ProviderType::Rancher),
+        _ => ProviderDetails::default(),
+    }
+}
+
+/// Detect the provider from the API server URL
+fn from_server(server: &str) -> ProviderDetails {
+    let Ok(uri) = server.parse::<http::Uri>() else {
+        return ProviderDetails::default();
+    };
+    let host = uri.host().unwrap_or_default().to_lowercase();
+    let labels: Vec<&str> = host.split('.').collect();
+    let label_before = |suffix: &str| {
+        let suffix_labels = suffix.split('.').count();
+        labels
+            .len()
+            .checked_sub(suffix_labels + 1)
+            .map(|i| labels[i].to_string())
+    };
+
+    // Rancher proxies downstream clusters under /k8s/clusters/<id>
+    if let Some((_, rest)) = uri.path().split_once("/k8s/clusters/") {
+        return ProviderDetails {
+            provider: Some(ProviderType::Rancher),
+            cluster_name: rest.split('/').next().map(str::to_string),
+            ..Default::default()
+        };
+    }
+
+    if host.ends_with(".eks.amazonaws.com") || host.ends_with(".eks.amazonaws.com.cn") {
+        // <id>.<shard>.<region>.eks.amazonaws.com
+        let suffix = if host.ends_with(".cn") { "eks.amazonaws.com.cn" } else { "eks.amazonaws.com" };
+        ProviderDetails {
+            provider: Some(ProviderType::Aws),
+            region: label_before(suffix),
+            ..Default::default()
+        }
+    } else if host.ends_with(".azmk8s.io") {
+        // <name>-dns-<hash>.hcp.<region>.azmk8s.io
+        ProviderDetails {
+            provider: Some(ProviderType::Azure),
+            region: label_before("azmk8s.io"),
+            ..Default::default()
+        }
+    } else if host.ends_with(".gke.goog") {
+        // gke-<hash>.<region>.gke.goog
+        ProviderDetails {
+            provider: Some(ProviderType::Gcp),
+            region: label_before("gke.goog"),
+            ..Default::default()
+        }
+    } else if ["container.googleapis.com", "gkeconnect.googleapis.com", "connectgateway.googleapis.com"]
+        .iter()
+        .any(|suffix| host.ends_with(suffix))
+    {
+        // Connect gateway: /v1/projects/<project>/locations/<location>/gkeMemberships/<name>
+        let segments: Vec<&str> = uri.path().split('/').collect();
+        let after = |key: &str| {
+            segments
+                .iter()
+                .position(|s| *s == key)
+                .and_then(|i| segments.get(i + 1))
+                .map(|s| s.to_string())
+        };
+        ProviderDetails {
+            provider: Some(ProviderType::Gcp),
+            region: after("locations"),
+            account: after("projects"),
+            cluster_name: after("gkeMemberships").or_else(|| after("memberships")),
+        }
+    } else if host.ends_with(".k8s.ondigitalocean.com") {
+        ProviderDetails::provider(ProviderType::DigitalOcean)
+    } else if host.ends_with(".openshiftapps.com") {
+        ProviderDetails::provider(ProviderType::OpenShift)
+    } else {
+        ProviderDetails::default()
+    }
+}
+
+/// Detect the provider from the naming conventions of provider CLIs
+fn from_name(name: &str) -> ProviderDetails {
+    // aws eks update-kubeconfig: arn:aws:eks:<region>:<account>:cluster/<name>
+    if name.starts_with("arn:aws") {
+        let parts: Vec<&str> = name.splitn(6, ':').collect();
+        if parts.len() == 6 && parts[2] == "eks" {
+            return ProviderDetails {
+                provider: Some(ProviderType::Aws),
+                region: Some(parts[3].to_string()),
+                account: Some(parts[4].to_string()),
+                cluster_name: parts[5].strip_prefix("cluster/").map(str::to_string),
+            };
+        }
+    }
+
+    // gcloud container clusters get-credentials: gke_<project>_<location>_<name>
+    if let Some(rest) = name.strip_prefix("gke_") {
+        let parts: Vec<&str> = rest.splitn(3, '_').collect();
+        if parts.len() == 3 {
+            return ProviderDetails {
+                provider: Some(ProviderType::Gcp),
+                account: Some(parts[0].to_string()),
+                region: Some(parts[1].to_string()),
+                cluster_name: Some(parts[2].to_string()),
+            };
+        }
+    }
+
+    // doctl kubernetes cluster kubeconfig save: do-<region>-<name>
+    if let Some(rest) = name.strip_prefix("do-") {
+        if let Some((region, cluster)) = rest.split_once('-').filter(|(region, _)| is_do_region(region)) {
+            return ProviderDetails {
+                provider: Some(ProviderType::DigitalOcean),
+                region: Some(region.to_string()),
+                cluster_name: Some(cluster.to_string()),
+                ..Default::default()
+            };
+        }
+    }
+
+    if let Some(cluster) = name.strip_prefix("kind-") {
+        return ProviderDetails {
+            provider: Some(ProviderType::Kind),
+            cluster_name: Some(cluster.to_string()),
+            ..Default::default()
+        };
+    }
+
+    if let Some(cluster) = name.strip_prefix("k3d-") {
+        return ProviderDetails {
+            provider: Some(ProviderType::K3d),
+            cluster_name: Some(cluster.to_string()),
+            ..Default::default()
+        };
+    }
+
+    // oc login: <namespace>/<api-host-with-dashes>:<port>/<user>
+    let parts: Vec<&str> = name.split('/').collect();
+    if parts.len() == 3 && parts[1].contains(':') {
+        return ProviderDetails::provider(ProviderType::OpenShift);
+    }
+
+    match name {
+        "minikube" => ProviderDetails::provider(ProviderType::Minikube),
+        "docker-desktop" | "rancher-desktop" | "microk8s" | "colima" | "orbstack" => {
+            ProviderDetails::provider(ProviderType::Local)
+        }
+        _ => ProviderDetails::default(),
+    }
+}
+
+/// Whether a name looks like a DigitalOcean region slug such as `nyc1` or `sfo3`
+fn is_do_region(region: &str) -> bool {
+    let digits = region.trim_start_matches(|c: char| c.is_ascii_lowercase());
+    digits.len() < region.len() && !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Value of a `--flag value` or `--flag=value` argument
+fn arg_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == flag {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(flag)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(str::to_string)
+        }
+    })
+}
+
+/// Value of an environment variable set on the exec plugin
+fn env_value(exec: &ExecConfig, name: &str) -> Option<String> {
+    exec.env
+        .as_ref()?
+        .iter()
+        .find(|var| var.get("name").map(String::as_str) == Some(name))
+        .and_then(|var| var.get("value").cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(details: ProviderDetails) -> (Option<ProviderType>, Option<String>, Option<String>, Option<String>) {
+        (details.provider, details.region, details.account, details.cluster_name)
+    }
+
+    fn some(value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    #[test]
+    fn detects_provider_from_name() {
+        let cases = [
+            (
+                "arn:aws:eks:eu-west-1:123456789012:cluster/prod",
+                (Some(ProviderType::Aws), some("eu-west-1"), some("123456789012"), some("prod")),
+            ),
+            ("arn:aws:iam::123456789012:role/admin", (None, None, None, None)),
+            (
+                "gke_my-project_us-central1-a_prod_cluster",
+                (Some(ProviderType::Gcp), some("us-central1-a"), some("my-project"), some("prod_cluster")),
+            ),
+            ("gke_incomplete", (None, None, None, None)),
+            ("do-nyc1-foo", (Some(ProviderType::DigitalOcean), some("nyc1"), None, some("foo"))),
+            ("do-sfo3-my-cluster", (Some(ProviderType::DigitalOcean), some("sfo3"), None, some("my-cluster"))),
+            ("do-not-use-prod", (None, None, None, None)),
+            ("do-1-foo", (None, None, None, None)),
+            ("do-nyc-foo", (None, None, None, None)),
+            ("kind-dev", (Some(ProviderType::Kind), None, None, some("dev"))),
+            ("k3d-local", (Some(ProviderType::K3d), None, None, some("local"))),
+            (
+                "default/api-cluster-example-com:6443/kube:admin",
+                (Some(ProviderType::OpenShift), None, None, None),
+            ),
+            ("minikube", (Some(ProviderType::Minikube), None, None, None)),
+            ("docker-desktop", (Some(ProviderType::Local), None, None, None)),
+            ("production", (None, None, None, None)),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(summary(from_name(name)), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn detects_provider_from_server() {
+        let cases = [
+            (
+                "https://ABCDEF.gr7.eu-west-1.eks.amazonaws.com",
+                (Some(ProviderType::Aws), some("eu-west-1"), None, None),
+            ),
+            (
+                "https://abcdef.yl4.cn-north-1.eks.amazonaws.com.cn",
+                (Some(ProviderType::Aws), some("cn-north-1"), None, None),
+            ),
+            (
+                "https://prod-dns-1a2b3c.hcp.westeurope.azmk8s.io:443",
+                (Some(ProviderType::Azure), some("westeurope"), None, None),
+            ),
+            ("https://gke-1a2b.us-east1.gke.goog", (Some(ProviderType::Gcp), some("us-east1"), None, None)),
+            (
+                "https://connectgateway.googleapis.com/v1/projects/123/locations/global/gkeMemberships/prod",
+                (Some(ProviderType::Gcp), some("global"), some("123"), some("prod")),
+            ),
+            (
+                "https://gkeconnect.googleapis.com/v1/projects/my-project/locations/global/gkeMemberships/prod",
+                (Some(ProviderType::Gcp), some("global"), some("my-project"), some("prod")),
+            ),
+            (
+                "https://1a2b.k8s.ondigitalocean.com",
+                (Some(ProviderType::DigitalOcean), None, None, None),
+            ),
+            (
+                "https://api.prod.abcd.p1.openshiftapps.com:6443",
+                (Some(ProviderType::OpenShift), None, None, None),
+            ),
+            (
+                "https://rancher.example.com/k8s/clusters/c-m-abcd",
+                (Some(ProviderType::Rancher), None, None, some("c-m-abcd")),
+            ),
+            ("https://127.0.0.1:6443", (None, None, None, None)),
+            ("not a url", (None, None, None, None)),
+        ];
+
+        for (server, expected) in cases {
+            assert_eq!(summary(from_server(server)), expected, "{}", server);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+
+/// Convert a Kubernetes quantity to a plain number (cores for CPU, bytes for memory)
+///
+/// Handles decimal (`m`, `k`, `M`, ...) and binary (`Ki`, `Mi`, ...) suffixes
+/// as well as exponent notation such as `1e3`.
+pub fn parse(quantity: &Quantity) -> Option<f64> {
+    parse_str(&quantity.0)
+}
+
+/// Convert a quantity string to a plain number
+pub fn parse_str(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier = match suffix {
+        "" => 1.0,
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 1024.0,
+        "Mi" => 1024f64.powi(2),
+        "Gi" => 1024f64.powi(3),
+        "Ti" => 1024f64.powi(4),
+        "Pi" => 1024f64.powi(5),
+        "Ei" => 1024f64.powi(6),
+        exponent if exponent.starts_with(['e', 'E']) => 10f64.powi(exponent[1..].parse().ok()?),
+        _ => return None,
+    };
+
+    Some(number * multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_suffixes() {
+        let cases = [
+            ("2", Some(2.0)),
+            ("0.5", Some(0.5)),
+            (" 250m ", Some(250.0 * 1e-3)),
+            ("100n", Some(100.0 * 1e-9)),
+            ("10u", Some(10.0 * 1e-6)),
+            ("1k", Some(1e3)),
+            ("1.5G", Some(1.5e9)),
+            ("2E", Some(2e18)),
+            ("128Ki", Some(128.0 * 1024.0)),
+            ("512Mi", Some(512.0 * 1024.0 * 1024.0)),
+            ("1Gi", Some(1024f64.powi(3))),
+            ("1e3", Some(1e3)),
+            ("5E2", Some(500.0)),
+            ("1e-3", Some(10f64.powi(-3))),
+            ("-1", Some(-1.0)),
+            ("", None),
+            ("Mi", None),
+            ("12Xi", None),
+            ("1ex", None),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(parse_str(value), expected, "{:?}", value);
+        }
+    }
+}
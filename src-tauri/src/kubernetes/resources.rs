@@ -1,88 +1,68 @@
-use anyhow::Result;
 use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Node, PersistentVolumeClaim, Pod, Secret, Service, ServiceAccount};
 use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
 use k8s_openapi::api::networking::v1::Ingress;
-use kube::api::ListParams;
-use kube::Api;
 
-use crate::types::{Resource, ResourcesResponse};
+use crate::error::AppError;
+use crate::kubernetes::listing::{self, Page};
+use crate::types::{ListOptions, Resource, ResourcesResponse};
 
 /// Get generic resources by type (Pods, Nodes, Services, ConfigMaps, etc.)
 pub async fn get_resources(
     client: kube::Client,
     resource_type: &str,
+    options: &ListOptions,
 ) -> ResourcesResponse {
-    let result = match resource_type {
-        "Pods" => fetch_resources::<Pod>(client).await,
-        "Nodes" => fetch_resources::<Node>(client).await,
-        "Services" => fetch_resources::<Service>(client).await,
-        "ConfigMaps" => fetch_resources::<ConfigMap>(client).await,
-        "Namespaces" => fetch_resources::<Namespace>(client).await,
-        "Deployments" => fetch_resources::<Deployment>(client).await,
-        "StatefulSets" => fetch_resources::<StatefulSet>(client).await,
-        "DaemonSets" => fetch_resources::<DaemonSet>(client).await,
-        "Ingresses" => fetch_resources::<Ingress>(client).await,
-        "Secrets" => fetch_resources::<Secret>(client).await,
-        "PVCs" => fetch_resources::<PersistentVolumeClaim>(client).await,
-        "ServiceAccounts" => fetch_resources::<ServiceAccount>(client).await,
-        _ => {
-            return ResourcesResponse {
-                items: vec![],
-                error: Some(format!("Unsupported resource type: {}", resource_type)),
-            }
-        }
-    };
-    
-    result.unwrap_or_else(|e| ResourcesResponse {
-        items: vec![],
-        error: Some(e.to_string()),
-    })
+    match resource_type {
+        "Pods" => to_response(listing::list::<Pod>(&client, options).await),
+        "Nodes" => to_response(listing::list_cluster::<Node>(&client, options).await),
+        "Services" => to_response(listing::list::<Service>(&client, options).await),
+        "ConfigMaps" => to_response(listing::list::<ConfigMap>(&client, options).await),
+        "Namespaces" => to_response(listing::list_cluster::<Namespace>(&client, options).await),
+        "Deployments" => to_response(listing::list::<Deployment>(&client, options).await),
+        "StatefulSets" => to_response(listing::list::<StatefulSet>(&client, options).await),
+        "DaemonSets" => to_response(listing::list::<DaemonSet>(&client, options).await),
+        "Ingresses" => to_response(listing::list::<Ingress>(&client, options).await),
+        "Secrets" => to_response(listing::list::<Secret>(&client, options).await),
+        "PVCs" => to_response(listing::list::<PersistentVolumeClaim>(&client, options).await),
+        "ServiceAccounts" => to_response(listing::list::<ServiceAccount>(&client, options).await),
+        _ => ResourcesResponse {
+            items: vec![],
+            continue_token: None,
+            error: Some(AppError::invalid_request(format!("Unsupported resource type: {}", resource_type))),
+        },
+    }
 }
 
-async fn fetch_resources<K>(client: kube::Client) -> Result<ResourcesResponse>
-where
-    K: kube::Resource,
-    <K as kube::Resource>::DynamicType: Default,
-    K: serde::de::DeserializeOwned + Clone + std::fmt::Debug,
-{
-    let api: Api<K> = Api::all(client);
-    
-    match api.list(&ListParams::default()).await {
-        Ok(list) => {
-            let items = list
-                .items
-                .iter()
-                .map(|item| {
-                    let meta = K::meta(item);
-                    Resource {
-                        id: meta.uid.clone().unwrap_or_default(),
-                        name: meta.name.clone().unwrap_or_default(),
-                        namespace: meta.namespace.clone().unwrap_or_else(|| "default".to_string()),
-                        creation_timestamp: meta
-                            .creation_timestamp
-                            .as_ref()
-                            .map(|ts| ts.0.to_rfc3339())
-                            .unwrap_or_default(),
-                    }
-                })
-                .collect();
-            
-            Ok(ResourcesResponse {
+fn to_response<K: kube::Resource>(result: Result<Page<K>, kube::Error>) -> ResourcesResponse {
+    match result {
+        Ok(page) => {
+            let items = page.items.iter().map(summarize).collect();
+
+            ResourcesResponse {
                 items,
+                continue_token: page.continue_token,
                 error: None,
-            })
-        }
-        Err(e) => {
-            let error_msg = if e.to_string().contains("connection refused") {
-                "Connection refused. Is the cluster running?".to_string()
-            } else {
-                e.to_string()
-            };
-            
-            Ok(ResourcesResponse {
-                items: vec![],
-                error: Some(error_msg),
-            })
+            }
         }
+        Err(e) => ResourcesResponse {
+            items: vec![],
+            continue_token: None,
+            error: Some(AppError::from(e)),
+        },
+    }
+}
+
+/// Summarise any object as a `Resource`
+pub fn summarize<K: kube::Resource>(item: &K) -> Resource {
+    let meta = item.meta();
+    Resource {
+        id: meta.uid.clone().unwrap_or_default(),
+        name: meta.name.clone().unwrap_or_default(),
+        namespace: meta.namespace.clone().unwrap_or_else(|| "default".to_string()),
+        creation_timestamp: meta
+            .creation_timestamp
+            .as_ref()
+            .map(|ts| ts.0.to_rfc3339())
+            .unwrap_or_default(),
     }
 }
@@ -0,0 +1,554 @@
+use chrono::Utc;
+use k8s_openapi::api::apps::v1::{ControllerRevision, DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::core::v1::{PodSpec, PodTemplateSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{ListParams, Patch, PatchParams};
+use kube::Api;
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::kubernetes::workloads;
+use crate::types::{RolloutRevision, RolloutStatus, RolloutUndo};
+
+/// Pod template annotation `kubectl rollout restart` sets to trigger a new rollout
+const RESTARTED_AT_ANNOTATION: &str = "kubectl.kubernetes.io/restartedAt";
+
+/// Annotation holding the revision number of a Deployment and its ReplicaSets
+const DEPLOYMENT_REVISION_ANNOTATION: &str = "deployment.kubernetes.io/revision";
+
+/// Annotation recording why a revision was made
+const CHANGE_CAUSE_ANNOTATION: &str = "kubernetes.io/change-cause";
+
+/// Label the Deployment controller adds to the pod template of each ReplicaSet
+const POD_TEMPLATE_HASH_LABEL: &str = "pod-template-hash";
+
+/// How often the rollout status is checked while waiting
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default time to wait for a rollout to finish
+const DEFAULT_STATUS_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Restart the pods of a Deployment, StatefulSet or DaemonSet with a rolling update
+///
+/// Like `kubectl rollout restart`, stamps the pod template with the current
+/// time so the controller sees a changed template and replaces every pod.
+pub async fn restart(client: kube::Client, kind: &str, namespace: &str, name: &str) -> Result<(), AppError> {
+    let patch = serde_json::json!({
+        "spec": { "template": { "metadata": { "annotations": {
+            RESTARTED_AT_ANNOTATION: Utc::now().to_rfc3339()
+        } } } }
+    });
+    let params = PatchParams::default();
+
+    match kind {
+        "Deployment" => {
+            let api: Api<Deployment> = Api::namespaced(client, namespace);
+            let deployment = api.get(name).await?;
+            if deployment.spec.is_some_and(|s| s.paused == Some(true)) {
+                return Err(AppError::invalid_request(format!(
+                    "Can't restart paused deployment {}; resume it first",
+                    name
+                )));
+            }
+            api.patch(name, &params, &Patch::Merge(&patch)).await?;
+        }
+        "StatefulSet" => {
+            let api: Api<StatefulSet> = Api::namespaced(client, namespace);
+            api.patch(name, &params, &Patch::Merge(&patch)).await?;
+        }
+        "DaemonSet" => {
+            let api: Api<DaemonSet> = Api::namespaced(client, namespace);
+            api.patch(name, &params, &Patch::Merge(&patch)).await?;
+        }
+        kind => return Err(unsupported(kind)),
+    }
+    Ok(())
+}
+
+/// Follow a rollout until it completes, reporting each change in progress
+///
+/// Mirrors `kubectl rollout status`: the messages are kubectl's, and a
+/// Deployment that exceeded its progress deadline fails instead of waiting out
+/// the timeout.
+pub async fn watch_status(
+    client: kube::Client,
+    kind: &str,
+    namespace: &str,
+    name: &str,
+    timeout: Option<Duration>,
+    on_progress: impl Fn(&RolloutStatus),
+) -> Result<RolloutStatus, AppError> {
+    let timeout = timeout.unwrap_or(DEFAULT_STATUS_TIMEOUT);
+    let poll = async {
+        let mut last_message = None;
+        loop {
+            let status = status(&client, kind, namespace, name).await?;
+            if last_message.as_ref() != Some(&status.message) {
+                on_progress(&status);
+                last_message = Some(status.message.clone());
+            }
+            if status.done {
+                return Ok(status);
+            }
+            tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+        }
+    };
+
+    tokio::time::timeout(timeout, poll).await.map_err(|_| AppError::Timeout {
+        message: format!(
+            "{} {} did not finish rolling out within {}s",
+            kind,
+            name,
+            timeout.as_secs()
+        ),
+    })?
+}
+
+/// Current rollout progress of a controller
+async fn status(client: &kube::Client, kind: &str, namespace: &str, name: &str) -> Result<RolloutStatus, AppError> {
+    match kind {
+        "Deployment" => deployment_status(&Api::namespaced(client.clone(), namespace).get(name).await?),
+        "StatefulSet" => stateful_set_status(&Api::namespaced(client.clone(), namespace).get(name).await?),
+        "DaemonSet" => daemon_set_status(&Api::namespaced(client.clone(), namespace).get(name).await?),
+        kind => Err(unsupported(kind)),
+    }
+}
+
+fn deployment_status(deployment: &Deployment) -> Result<RolloutStatus, AppError> {
+    let name = deployment.metadata.name.clone().unwrap_or_default();
+    let status = deployment.status.clone().unwrap_or_default();
+    let desired = deployment.spec.as_ref().and_then(|s| s.replicas).unwrap_or(1);
+    let updated = status.updated_replicas.unwrap_or(0);
+    let available = status.available_replicas.unwrap_or(0);
+    let total = status.replicas.unwrap_or(0);
+
+    let mut rollout = RolloutStatus {
+        message: String::new(),
+        done: false,
+        desired_replicas: desired,
+        updated_replicas: updated,
+        ready_replicas: status.ready_replicas.unwrap_or(0),
+        available_replicas: available,
+    };
+
+    if !is_observed(&deployment.metadata, status.observed_generation) {
+        rollout.message = "Waiting for deployment spec update to be observed...".to_string();
+        return Ok(rollout);
+    }
+
+    let deadline_exceeded = status.conditions.iter().flatten().any(|c| {
+        c.type_ == "Progressing" && c.reason.as_deref() == Some("ProgressDeadlineExceeded")
+    });
+    if deadline_exceeded {
+        return Err(AppError::Timeout {
+            message: format!("deployment \"{}\" exceeded its progress deadline", name),
+        });
+    }
+
+    rollout.message = if updated < desired {
+        format!(
+            "Waiting for deployment \"{}\" rollout to finish: {} out of {} new replicas have been updated...",
+            name, updated, desired
+        )
+    } else if total > updated {
+        format!(
+            "Waiting for deployment \"{}\" rollout to finish: {} old replicas are pending termination...",
+            name,
+            total - updated
+        )
+    } else if available < updated {
+        format!(
+            "Waiting for deployment \"{}\" rollout to finish: {} of {} updated replicas are available...",
+            name, available, updated
+        )
+    } else {
+        rollout.done = true;
+        format!("deployment \"{}\" successfully rolled out", name)
+    };
+    Ok(rollout)
+}
+
+fn stateful_set_status(stateful_set: &StatefulSet) -> Result<RolloutStatus, AppError> {
+    let spec = stateful_set.spec.clone().unwrap_or_default();
+    let status = stateful_set.status.clone().unwrap_or_default();
+    let strategy = spec.update_strategy.unwrap_or_default();
+    if strategy.type_.as_deref().is_some_and(|t| t != "RollingUpdate") {
+        return Err(AppError::invalid_request(
+            "Rollout status is only available for the RollingUpdate strategy",
+        ));
+    }
+
+    let desired = spec.replicas.unwrap_or(1);
+    let updated = status.updated_replicas.unwrap_or(0);
+    let ready = status.ready_replicas.unwrap_or(0);
+    let mut rollout = RolloutStatus {
+        message: String::new(),
+        done: false,
+        desired_replicas: desired,
+        updated_replicas: updated,
+        ready_replicas: ready,
+        available_replicas: status.available_replicas.unwrap_or(0),
+    };
+
+    if status.observed_generation.unwrap_or(0) == 0
+        || !is_observed(&stateful_set.metadata, status.observed_generation)
+    {
+        rollout.message = "Waiting for statefulset spec update to be observed...".to_string();
+        return Ok(rollout);
+    }
+    if ready < desired {
+        rollout.message = format!("Waiting for {} pods to be ready...", desired - ready);
+        return Ok(rollout);
+    }
+
+    let partition = strategy.rolling_update.and_then(|r| r.partition).unwrap_or(0);
+    if partition > 0 {
+        if updated < desired - partition {
+            rollout.message = format!(
+                "Waiting for partitioned roll out to finish: {} out of {} new pods have been updated...",
+                updated,
+                desired - partition
+            );
+        } else {
+            rollout.done = true;
+            rollout.message = format!("partitioned roll out complete: {} new pods have been updated...", updated);
+        }
+        return Ok(rollout);
+    }
+
+    let update_revision = status.update_revision.unwrap_or_default();
+    if status.current_revision.as_deref() != Some(update_revision.as_str()) {
+        rollout.message = format!(
+            "waiting for statefulset rolling update to complete {} pods at revision {}...",
+            updated, update_revision
+        );
+    } else {
+        rollout.done = true;
+        rollout.message = format!(
+            "statefulset rolling update complete {} pods at revision {}...",
+            status.current_replicas.unwrap_or(0),
+            update_revision
+        );
+    }
+    Ok(rollout)
+}
+
+fn daemon_set_status(daemon_set: &DaemonSet) -> Result<RolloutStatus, AppError> {
+    let name = daemon_set.metadata.name.clone().unwrap_or_default();
+    let strategy = daemon_set.spec.as_ref().and_then(|s| s.update_strategy.as_ref());
+    if strategy.and_then(|s| s.type_.as_deref()).is_some_and(|t| t != "RollingUpdate") {
+        return Err(AppError::invalid_request(
+            "Rollout status is only available for the RollingUpdate strategy",
+        ));
+    }
+
+    let status = daemon_set.status.clone().unwrap_or_default();
+    let desired = status.desired_number_scheduled;
+    let updated = status.updated_number_scheduled.unwrap_or(0);
+    let available = status.number_available.unwrap_or(0);
+    let mut rollout = RolloutStatus {
+        message: String::new(),
+        done: false,
+        desired_replicas: desired,
+        updated_replicas: updated,
+        ready_replicas: status.number_ready,
+        available_replicas: available,
+    };
+
+    rollout.message = if !is_observed(&daemon_set.metadata, status.observed_generation) {
+        "Waiting for daemon set spec update to be observed...".to_string()
+    } else if updated < desired {
+        format!(
+            "Waiting for daemon set \"{}\" rollout to finish: {} out of {} new pods have been updated...",
+            name, updated, desired
+        )
+    } else if available < desired {
+        format!(
+            "Waiting for daemon set \"{}\" rollout to finish: {} of {} updated pods are available...",
+            name, available, desired
+        )
+    } else {
+        rollout.done = true;
+        format!("daemon set \"{}\" successfully rolled out", name)
+    };
+    Ok(rollout)
+}
+
+/// Whether the controller has caught up with the latest spec change
+fn is_observed(metadata: &ObjectMeta, observed_generation: Option<i64>) -> bool {
+    metadata.generation.unwrap_or(0) <= observed_generation.unwrap_or(0)
+}
+
+/// Revisions of a controller, oldest first
+///
+/// Deployments keep their revisions as ReplicaSets, StatefulSets and
+/// DaemonSets as ControllerRevisions; both are matched by owner uid.
+pub async fn history(
+    client: kube::Client,
+    kind: &str,
+    namespace: &str,
+    name: &str,
+) -> Result<Vec<RolloutRevision>, AppError> {
+    let mut revisions = match kind {
+        "Deployment" => deployment_history(&client, namespace, name).await?,
+        "StatefulSet" => {
+            let stateful_set: StatefulSet = Api::namespaced(client.clone(), namespace).get(name).await?;
+            let update_revision = stateful_set.status.and_then(|s| s.update_revision);
+            let mut revisions = controller_revisions(&client, namespace, &stateful_set.metadata).await?;
+            for revision in &mut revisions {
+                revision.current = update_revision.as_deref() == Some(revision.name.as_str());
+            }
+            revisions
+        }
+        "DaemonSet" => {
+            let daemon_set: DaemonSet = Api::namespaced(client.clone(), namespace).get(name).await?;
+            let mut revisions = controller_revisions(&client, namespace, &daemon_set.metadata).await?;
+            // DaemonSets don't record their revision; the newest one is live
+            let newest = revisions.iter().map(|r| r.revision).max();
+            for revision in &mut revisions {
+                revision.current = Some(revision.revision) == newest;
+            }
+            revisions
+        }
+        kind => return Err(unsupported(kind)),
+    };
+    revisions.sort_by_key(|r| r.revision);
+    Ok(revisions)
+}
+
+async fn deployment_history(
+    client: &kube::Client,
+    namespace: &str,
+    name: &str,
+) -> Result<Vec<RolloutRevision>, AppError> {
+    let deployment: Deployment = Api::namespaced(client.clone(), namespace).get(name).await?;
+    let current = revision_annotation(&deployment.metadata);
+
+    Ok(owned_replica_sets(client, namespace, &deployment)
+        .await?
+        .iter()
+        .filter_map(|rs| {
+            let revision = revision_annotation(&rs.metadata)?;
+            let template = rs.spec.as_ref().and_then(|s| s.template.as_ref());
+            Some(RolloutRevision {
+                revision,
+                name: rs.metadata.name.clone().unwrap_or_default(),
+                change_cause: change_cause(&rs.metadata),
+                images: workloads::images(template.and_then(|t| t.spec.as_ref())),
+                created: rs.metadata.creation_timestamp.as_ref().map(|t| t.0.to_rfc3339()),
+                current: Some(revision) == current,
+            })
+        })
+        .collect())
+}
+
+async fn controller_revisions(
+    client: &kube::Client,
+    namespace: &str,
+    owner: &ObjectMeta,
+) -> Result<Vec<RolloutRevision>, AppError> {
+    Ok(owned_controller_revisions(client, namespace, owner)
+        .await?
+        .iter()
+        .map(|cr| {
+            let pod_spec = cr
+                .data
+                .as_ref()
+                .and_then(|d| d.0.pointer("/spec/template/spec"))
+                .and_then(|s| serde_json::from_value::<PodSpec>(s.clone()).ok());
+            RolloutRevision {
+                revision: cr.revision,
+                name: cr.metadata.name.clone().unwrap_or_default(),
+                change_cause: change_cause(&cr.metadata),
+                images: workloads::images(pod_spec.as_ref()),
+                created: cr.metadata.creation_timestamp.as_ref().map(|t| t.0.to_rfc3339()),
+                current: false,
+            }
+        })
+        .collect())
+}
+
+/// Roll a controller back to an earlier revision, by default the one before the current
+///
+/// Like `kubectl rollout undo`, a Deployment gets the ReplicaSet's pod template
+/// back, while StatefulSets and DaemonSets re-apply the template patch stored in
+/// the ControllerRevision.
+pub async fn undo(
+    client: kube::Client,
+    kind: &str,
+    namespace: &str,
+    name: &str,
+    to_revision: Option<i64>,
+) -> Result<RolloutUndo, AppError> {
+    match kind {
+        "Deployment" => undo_deployment(client, namespace, name, to_revision).await,
+        "StatefulSet" => {
+            let api: Api<StatefulSet> = Api::namespaced(client.clone(), namespace);
+            let stateful_set = api.get(name).await?;
+            let current = stateful_set.status.as_ref().and_then(|s| s.update_revision.clone());
+            let revisions = owned_controller_revisions(&client, namespace, &stateful_set.metadata).await?;
+            let current = revisions
+                .iter()
+                .find(|r| r.metadata.name == current)
+                .map(|r| r.revision);
+            let target = pick_revision(&revisions, |r| Some(r.revision), current, to_revision)?;
+            apply_controller_revision(&api, name, target, current).await
+        }
+        "DaemonSet" => {
+            let api: Api<DaemonSet> = Api::namespaced(client.clone(), namespace);
+            let daemon_set = api.get(name).await?;
+            let revisions = owned_controller_revisions(&client, namespace, &daemon_set.metadata).await?;
+            let current = revisions.iter().map(|r| r.revision).max();
+            let target = pick_revision(&revisions, |r| Some(r.revision), current, to_revision)?;
+            apply_controller_revision(&api, name, target, current).await
+        }
+        kind => Err(unsupported(kind)),
+    }
+}
+
+async fn undo_deployment(
+    client: kube::Client,
+    namespace: &str,
+    name: &str,
+    to_revision: Option<i64>,
+) -> Result<RolloutUndo, AppError> {
+    let api: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+    let deployment = api.get(name).await?;
+    if deployment.spec.as_ref().is_some_and(|s| s.paused == Some(true)) {
+        return Err(AppError::invalid_request(format!(
+            "Can't roll back paused deployment {}; resume it first",
+            name
+        )));
+    }
+
+    let current = revision_annotation(&deployment.metadata);
+    let replica_sets = owned_replica_sets(&client, namespace, &deployment).await?;
+    let target = pick_revision(&replica_sets, |rs| revision_annotation(&rs.metadata), current, to_revision)?;
+    let revision = revision_annotation(&target.metadata).unwrap_or_default();
+
+    let template = target
+        .spec
+        .as_ref()
+        .and_then(|s| s.template.as_ref())
+        .map(without_hash)
+        .ok_or_else(|| AppError::other(format!("Revision {} has no pod template", revision)))?;
+
+    let live_template = deployment.spec.map(|s| s.template).unwrap_or_default();
+    if without_hash(&live_template) == template {
+        return Ok(RolloutUndo {
+            revision,
+            skipped: true,
+        });
+    }
+
+    let mut template = serde_json::to_value(&template).map_err(|e| AppError::other(e.to_string()))?;
+    template["$patch"] = serde_json::json!("replace");
+    let patch = serde_json::json!({ "spec": { "template": template } });
+    api.patch(name, &PatchParams::default(), &Patch::Strategic(&patch)).await?;
+
+    Ok(RolloutUndo {
+        revision,
+        skipped: false,
+    })
+}
+
+/// Re-apply the template patch a ControllerRevision stores
+async fn apply_controller_revision<K>(
+    api: &Api<K>,
+    name: &str,
+    target: &ControllerRevision,
+    current: Option<i64>,
+) -> Result<RolloutUndo, AppError>
+where
+    K: kube::Resource + Clone + serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    if Some(target.revision) == current {
+        return Ok(RolloutUndo {
+            revision: target.revision,
+            skipped: true,
+        });
+    }
+    let data = target
+        .data
+        .as_ref()
+        .ok_or_else(|| AppError::other(format!("Revision {} has no data", target.revision)))?;
+    api.patch(name, &PatchParams::default(), &Patch::Strategic(&data.0)).await?;
+
+    Ok(RolloutUndo {
+        revision: target.revision,
+        skipped: false,
+    })
+}
+
+/// The requested revision, or the newest one before the current revision
+fn pick_revision<T>(
+    revisions: &[T],
+    revision_of: impl Fn(&T) -> Option<i64>,
+    current: Option<i64>,
+    to_revision: Option<i64>,
+) -> Result<&T, AppError> {
+    let found = match to_revision {
+        Some(wanted) => revisions.iter().find(|r| revision_of(r) == Some(wanted)),
+        None => revisions
+            .iter()
+            .filter(|r| revision_of(r).is_some() && revision_of(r) != current)
+            .max_by_key(|r| revision_of(r)),
+    };
+    found.ok_or_else(|| AppError::NotFound {
+        message: match to_revision {
+            Some(wanted) => format!("Revision {} not found", wanted),
+            None => "No previous revision to roll back to".to_string(),
+        },
+    })
+}
+
+async fn owned_replica_sets(
+    client: &kube::Client,
+    namespace: &str,
+    deployment: &Deployment,
+) -> Result<Vec<ReplicaSet>, AppError> {
+    let uid = deployment.metadata.uid.clone();
+    let list = Api::<ReplicaSet>::namespaced(client.clone(), namespace)
+        .list(&ListParams::default())
+        .await?;
+    Ok(list
+        .items
+        .into_iter()
+        .filter(|rs| uid.is_some() && workloads::controller_uid(&rs.metadata) == uid)
+        .collect())
+}
+
+async fn owned_controller_revisions(
+    client: &kube::Client,
+    namespace: &str,
+    owner: &ObjectMeta,
+) -> Result<Vec<ControllerRevision>, AppError> {
+    let list = Api::<ControllerRevision>::namespaced(client.clone(), namespace)
+        .list(&ListParams::default())
+        .await?;
+    Ok(list
+        .items
+        .into_iter()
+        .filter(|cr| owner.uid.is_some() && workloads::controller_uid(&cr.metadata) == owner.uid)
+        .collect())
+}
+
+fn without_hash(template: &PodTemplateSpec) -> PodTemplateSpec {
+    let mut template = template.clone();
+    if let Some(labels) = template.metadata.as_mut().and_then(|m| m.labels.as_mut()) {
+        labels.remove(POD_TEMPLATE_HASH_LABEL);
+    }
+    template
+}
+
+fn revision_annotation(metadata: &ObjectMeta) -> Option<i64> {
+    metadata.annotations.as_ref()?.get(DEPLOYMENT_REVISION_ANNOTATION)?.parse().ok()
+}
+
+fn change_cause(metadata: &ObjectMeta) -> Option<String> {
+    metadata.annotations.as_ref()?.get(CHANGE_CAUSE_ANNOTATION).cloned()
+}
+
+fn unsupported(kind: &str) -> AppError {
+    AppError::invalid_request(format!("{} has no rollouts", kind))
+}
@@ -0,0 +1,89 @@
+use k8s_openapi::api::apps::v1::{Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::autoscaling::v2::HorizontalPodAutoscaler;
+use k8s_openapi::NamespaceResourceScope;
+use kube::api::{ListParams, Patch, PatchParams};
+use kube::Api;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+use crate::error::AppError;
+use crate::types::{ScaleRequest, ScaleResult};
+
+/// Set the replica count of a Deployment, StatefulSet or ReplicaSet through its scale subresource
+///
+/// Refuses targets an HorizontalPodAutoscaler manages, since the autoscaler
+/// would undo the change, unless the request explicitly ignores the HPA.
+pub async fn scale_workload(client: kube::Client, request: &ScaleRequest) -> Result<ScaleResult, AppError> {
+    if request.replicas < 0 {
+        return Err(AppError::invalid_request("Replicas cannot be negative"));
+    }
+
+    if !request.ignore_hpa {
+        if let Some(hpa) = find_hpa(&client, request).await? {
+            return Err(AppError::invalid_request(hpa));
+        }
+    }
+
+    match request.kind.as_str() {
+        "Deployment" => scale::<Deployment>(client, request).await,
+        "StatefulSet" => scale::<StatefulSet>(client, request).await,
+        "ReplicaSet" => scale::<ReplicaSet>(client, request).await,
+        kind => Err(AppError::invalid_request(format!("{} cannot be scaled", kind))),
+    }
+}
+
+async fn scale<K>(client: kube::Client, request: &ScaleRequest) -> Result<ScaleResult, AppError>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope> + Clone + DeserializeOwned + Debug,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let api: Api<K> = Api::namespaced(client, &request.namespace);
+    let previous = api.get_scale(&request.name).await?;
+    let previous_replicas = previous.spec.and_then(|s| s.replicas).unwrap_or(0);
+
+    let params = PatchParams {
+        dry_run: request.dry_run,
+        ..Default::default()
+    };
+    let patch = serde_json::json!({ "spec": { "replicas": request.replicas } });
+    let scaled = api.patch_scale(&request.name, &params, &Patch::Merge(&patch)).await?;
+
+    Ok(ScaleResult {
+        kind: request.kind.clone(),
+        namespace: request.namespace.clone(),
+        name: request.name.clone(),
+        previous_replicas,
+        replicas: scaled.spec.and_then(|s| s.replicas).unwrap_or(request.replicas),
+        dry_run: request.dry_run,
+    })
+}
+
+/// Describe the HPA that targets the workload, if any
+async fn find_hpa(client: &kube::Client, request: &ScaleRequest) -> Result<Option<String>, AppError> {
+    let hpas: Api<HorizontalPodAutoscaler> = Api::namespaced(client.clone(), &request.namespace);
+    let list = match hpas.list(&ListParams::default()).await {
+        Ok(list) => list,
+        // Not being able to see HPAs shouldn't block scaling
+        Err(kube::Error::Api(response)) if response.code == 403 => {
+            log::info!("Could not check for HPAs: {}", response.message);
+            return Ok(None);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(list.items.into_iter().find_map(|hpa| {
+        let spec = hpa.spec?;
+        let target = &spec.scale_target_ref;
+        if target.kind != request.kind || target.name != request.name {
+            return None;
+        }
+        Some(format!(
+            "{} {} is managed by HorizontalPodAutoscaler {} ({}-{} replicas), which would revert the change",
+            request.kind,
+            request.name,
+            hpa.metadata.name.unwrap_or_default(),
+            spec.min_replicas.unwrap_or(1),
+            spec.max_replicas
+        ))
+    }))
+}
@@ -0,0 +1,498 @@
+use futures::future::BoxFuture;
+use futures::{FutureExt, StreamExt};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Node, PersistentVolumeClaim, Pod, Secret, Service, ServiceAccount};
+use k8s_openapi::api::networking::v1::Ingress;
+use k8s_openapi::NamespaceResourceScope;
+use kube::api::{ListParams, WatchEvent, WatchParams};
+use kube::Api;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::{AbortHandle, JoinSet};
+
+use crate::error::AppError;
+use crate::kubernetes::resources;
+use crate::types::{ListOptions, Resource, ResourceEvent};
+
+/// Events arriving within this window are merged into one batch
+const COALESCE_WINDOW: Duration = Duration::from_millis(250);
+
+/// Server-side timeout of each watch request; the watch is resumed afterwards
+const WATCH_TIMEOUT_SECS: u32 = 290;
+
+/// Longest wait between retries after a failed list or watch
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Running watch subscriptions, keyed by the id handed to the frontend
+#[derive(Default)]
+pub struct Subscriptions {
+    next_id: AtomicU64,
+    tasks: Mutex<HashMap<String, AbortHandle>>,
+}
+
+impl Subscriptions {
+    /// Track a watch task and return its subscription id
+    pub fn insert(&self, task: AbortHandle) -> String {
+        let id = format!("watch-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|_, t| !t.is_finished());
+        tasks.insert(id.clone(), task);
+        id
+    }
+
+    /// Stop a watch, returning whether it was running
+    pub fn remove(&self, id: &str) -> bool {
+        match self.tasks.lock().unwrap().remove(id) {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Change seen by one scope's watch loop before coalescing
+enum Change {
+    Applied { scope: Option<String>, event: ResourceEvent },
+    Reset { scope: Option<String>, items: Vec<Resource> },
+    Failed(AppError),
+}
+
+/// Build the task that watches a resource type and reports batches of events
+///
+/// Returns an error straight away for unsupported types so the caller can
+/// reject the subscription before spawning anything. The task ends when
+/// `on_batch` returns false, e.g. because the frontend closed the channel.
+pub fn watch_resources<F>(
+    client: kube::Client,
+    resource_type: &str,
+    options: ListOptions,
+    on_batch: F,
+) -> Result<BoxFuture<'static, ()>, AppError>
+where
+    F: FnMut(Vec<ResourceEvent>) -> bool + Send + 'static,
+{
+    let namespaces = options.namespaces.clone();
+    let watch = match resource_type {
+        "Pods" => watch(namespaced::<Pod>(&client, &namespaces), options, on_batch).boxed(),
+        "Nodes" => watch(vec![(None, Api::<Node>::all(client))], options, on_batch).boxed(),
+        "Services" => watch(namespaced::<Service>(&client, &namespaces), options, on_batch).boxed(),
+        "ConfigMaps" => watch(namespaced::<ConfigMap>(&client, &namespaces), options, on_batch).boxed(),
+        "Namespaces" => watch(vec![(None, Api::<Namespace>::all(client))], options, on_batch).boxed(),
+        "Deployments" => watch(namespaced::<Deployment>(&client, &namespaces), options, on_batch).boxed(),
+        "StatefulSets" => watch(namespaced::<StatefulSet>(&client, &namespaces), options, on_batch).boxed(),
+        "DaemonSets" => watch(namespaced::<DaemonSet>(&client, &namespaces), options, on_batch).boxed(),
+        "Ingresses" => watch(namespaced::<Ingress>(&client, &namespaces), options, on_batch).boxed(),
+        "Secrets" => watch(namespaced::<Secret>(&client, &namespaces), options, on_batch).boxed(),
+        "PVCs" => watch(namespaced::<PersistentVolumeClaim>(&client, &namespaces), options, on_batch).boxed(),
+        "ServiceAccounts" => watch(namespaced::<ServiceAccount>(&client, &namespaces), options, on_batch).boxed(),
+        _ => {
+            return Err(AppError::invalid_request(format!(
+                "Unsupported resource type: {}",
+                resource_type
+            )))
+        }
+    };
+    Ok(watch)
+}
+
+/// One API per requested namespace, or a single cluster-wide one
+fn namespaced<K>(client: &kube::Client, namespaces: &[String]) -> Vec<(Option<String>, Api<K>)>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope>,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    if namespaces.is_empty() {
+        return vec![(None, Api::all(client.clone()))];
+    }
+    namespaces
+        .iter()
+        .map(|ns| (Some(ns.clone()), Api::namespaced(client.clone(), ns)))
+        .collect()
+}
+
+/// Watch every scope and deliver coalesced batches until `on_batch` asks to stop
+async fn watch<K, F>(apis: Vec<(Option<String>, Api<K>)>, options: ListOptions, mut on_batch: F)
+where
+    K: kube::Resource + Clone + DeserializeOwned + Debug + Send + 'static,
+    <K as kube::Resource>::DynamicType: Default,
+    F: FnMut(Vec<ResourceEvent>) -> bool,
+{
+    let (sender, mut changes) = mpsc::unbounded_channel();
+    // Dropping the set when this future is aborted stops every scope
+    let mut scopes = JoinSet::new();
+    for (scope, api) in apis {
+        scopes.spawn(watch_scope(api, scope, options.clone(), sender.clone()));
+    }
+    drop(sender);
+
+    let mut pending = Batch::default();
+    let mut flush = tokio::time::interval(COALESCE_WINDOW);
+    loop {
+        tokio::select! {
+            change = changes.recv() => match change {
+                Some(change) => pending.add(change),
+                None => break,
+            },
+            _ = flush.tick() => {
+                if !pending.is_empty() && !on_batch(pending.take()) {
+                    return;
+                }
+            }
+        }
+    }
+
+    if !pending.is_empty() {
+        on_batch(pending.take());
+    }
+}
+
+/// List, then watch from the list's resourceVersion, relisting when it expires (410 Gone)
+async fn watch_scope<K>(api: Api<K>, scope: Option<String>, options: ListOptions, changes: mpsc::UnboundedSender<Change>)
+where
+    K: kube::Resource + Clone + DeserializeOwned + Debug,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let label_selector = options.label_selector.filter(|s| !s.is_empty());
+    let field_selector = options.field_selector.filter(|s| !s.is_empty());
+    let list_params = ListParams {
+        label_selector: label_selector.clone(),
+        field_selector: field_selector.clone(),
+        ..Default::default()
+    };
+    let watch_params = WatchParams {
+        label_selector,
+        field_selector,
+        timeout: Some(WATCH_TIMEOUT_SECS),
+        bookmarks: true,
+        ..Default::default()
+    };
+    let mut retry = RetryDelay::default();
+
+    'relist: loop {
+        let list = match api.list(&list_params).await {
+            Ok(list) => list,
+            Err(e) => {
+                if changes.send(Change::Failed(e.into())).is_err() {
+                    return;
+                }
+                retry.wait().await;
+                continue;
+            }
+        };
+        retry.reset();
+
+        let mut resource_version = list.metadata.resource_version.clone().unwrap_or_default();
+        let items = list.items.iter().map(resources::summarize).collect();
+        if changes.send(Change::Reset { scope: scope.clone(), items }).is_err() {
+            return;
+        }
+
+        loop {
+            let stream = match api.watch(&watch_params, &resource_version).await {
+                Ok(stream) => stream,
+                Err(kube::Error::Api(response)) if response.code == 410 => continue 'relist,
+                Err(e) => {
+                    if changes.send(Change::Failed(e.into())).is_err() {
+                        return;
+                    }
+                    retry.wait().await;
+                    continue;
+                }
+            };
+            let mut stream = std::pin::pin!(stream);
+
+            while let Some(event) = stream.next().await {
+                let (object, event): (K, fn(Resource) -> ResourceEvent) = match event {
+                    Ok(WatchEvent::Added(object)) => (object, |object| ResourceEvent::Added { object }),
+                    Ok(WatchEvent::Modified(object)) => (object, |object| ResourceEvent::Modified { object }),
+                    Ok(WatchEvent::Deleted(object)) => (object, |object| ResourceEvent::Deleted { object }),
+                    Ok(WatchEvent::Bookmark(bookmark)) => {
+                        resource_version = bookmark.metadata.resource_version;
+                        continue;
+                    }
+                    Ok(WatchEvent::Error(response)) if response.code == 410 => continue 'relist,
+                    Ok(WatchEvent::Error(response)) => {
+                        if changes.send(Change::Failed(kube::Error::Api(response).into())).is_err() {
+                            return;
+                        }
+                        retry.wait().await;
+                        break;
+                    }
+                    Err(kube::Error::Api(response)) if response.code == 410 => continue 'relist,
+                    Err(e) => {
+                        if changes.send(Change::Failed(e.into())).is_err() {
+                            return;
+                        }
+                        retry.wait().await;
+                        break;
+                    }
+                };
+
+                retry.reset();
+                if let Some(version) = object.meta().resource_version.clone() {
+                    resource_version = version;
+                }
+                let event = event(resources::summarize(&object));
+                if changes.send(Change::Applied { scope: scope.clone(), event }).is_err() {
+                    return;
+                }
+            }
+            // The server closes watches after the timeout; resume from the last version seen
+        }
+    }
+}
+
+/// Events waiting to be sent, merged per object
+#[derive(Default)]
+struct Batch {
+    events: Vec<Option<(Option<String>, ResourceEvent)>>,
+    /// Object uid -> position in `events`
+    positions: HashMap<String, usize>,
+    /// Uids that were new when first seen in this batch
+    added: Vec<String>,
+    /// Last error reported, kept across batches until a list or event succeeds
+    last_error: Option<String>,
+}
+
+impl Batch {
+    fn add(&mut self, change: Change) {
+        match change {
+            Change::Applied { scope, event } => {
+                self.last_error = None;
+                self.apply(scope, event);
+            }
+            Change::Reset { scope, items } => {
+                self.last_error = None;
+                // A relist supersedes anything still pending for the same scope, or
+                // for every scope when it covers the whole subscription
+                for slot in &mut self.events {
+                    let superseded = |(s, e): &(Option<String>, ResourceEvent)| {
+                        (scope.is_none() || *s == scope) && !matches!(e, ResourceEvent::Error { .. })
+                    };
+                    if slot.as_ref().is_some_and(superseded) {
+                        *slot = None;
+                    }
+                }
+                if scope.is_none() {
+                    self.positions.clear();
+                    self.added.clear();
+                } else {
+                    // Other scopes keep coalescing; only uids whose event was dropped are forgotten
+                    let events = &self.events;
+                    self.positions.retain(|_, i| events[*i].is_some());
+                    let positions = &self.positions;
+                    self.added.retain(|uid| positions.contains_key(uid));
+                }
+                self.events.push(Some((scope.clone(), ResourceEvent::Reset { namespace: scope, items })));
+            }
+            Change::Failed(error) => {
+                // Retries tend to fail the same way; report each distinct error once
+                if self.last_error.as_deref() != Some(error.message()) {
+                    self.last_error = Some(error.message().to_string());
+                    self.events.push(Some((None, ResourceEvent::Error { error })));
+                }
+            }
+        }
+    }
+
+    fn apply(&mut self, scope: Option<String>, event: ResourceEvent) {
+        let uid = match &event {
+            ResourceEvent::Added { object } | ResourceEvent::Modified { object } | ResourceEvent::Deleted { object } => {
+                object.id.clone()
+            }
+            _ => return,
+        };
+        let previous = self.positions.get(&uid).copied();
+        let was_added = self.added.contains(&uid);
+
+        let event = match event {
+            ResourceEvent::Added { object } => {
+                self.added.push(uid.clone());
+                ResourceEvent::Added { object }
+            }
+            // Still new to the frontend, so it is reported as added with its latest state
+            ResourceEvent::Modified { object } if was_added => ResourceEvent::Added { object },
+            // Created and deleted within the window: nothing to report
+            ResourceEvent::Deleted { .. } if was_added => {
+                if let Some(i) = previous {
+                    self.events[i] = None;
+                }
+                self.positions.remove(&uid);
+                self.added.retain(|u| *u != uid);
+                return;
+            }
+            event => event,
+        };
+
+        match previous {
+            Some(i) => self.events[i] = Some((scope, event)),
+            None => {
+                self.positions.insert(uid, self.events.len());
+                self.events.push(Some((scope, event)));
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.events.iter().all(Option::is_none)
+    }
+
+    fn take(&mut self) -> Vec<ResourceEvent> {
+        self.positions.clear();
+        self.added.clear();
+        self.events.drain(..).flatten().map(|(_, event)| event).collect()
+    }
+}
+
+/// Exponential backoff between failed list or watch attempts
+pub struct RetryDelay(Duration);
+
+impl Default for RetryDelay {
+    fn default() -> Self {
+        RetryDelay(Duration::from_secs(1))
+    }
+}
+
+impl RetryDelay {
+    pub async fn wait(&mut self) {
+        tokio::time::sleep(self.0).await;
+        self.0 = (self.0 * 2).min(MAX_RETRY_DELAY);
+    }
+
+    pub fn reset(&mut self) {
+        *self = RetryDelay::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(uid: &str, namespace: &str) -> Resource {
+        Resource {
+            id: uid.to_string(),
+            name: uid.to_string(),
+            namespace: namespace.to_string(),
+            creation_timestamp: String::new(),
+        }
+    }
+
+    fn applied(kind: &str, uid: &str, namespace: &str) -> Change {
+        let object = object(uid, namespace);
+        let event = match kind {
+            "added" => ResourceEvent::Added { object },
+            "modified" => ResourceEvent::Modified { object },
+            "deleted" => ResourceEvent::Deleted { object },
+            _ => unreachable!(),
+        };
+        Change::Applied { scope: Some(namespace.to_string()), event }
+    }
+
+    fn reset(scope: Option<&str>) -> Change {
+        Change::Reset { scope: scope.map(str::to_string), items: vec![] }
+    }
+
+    fn failed(message: &str) -> Change {
+        Change::Failed(AppError::other(message))
+    }
+
+    fn describe(events: Vec<ResourceEvent>) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| match event {
+                ResourceEvent::Added { object } => format!("added {}", object.id),
+                ResourceEvent::Modified { object } => format!("modified {}", object.id),
+                ResourceEvent::Deleted { object } => format!("deleted {}", object.id),
+                ResourceEvent::Reset { namespace, .. } => format!("reset {}", namespace.as_deref().unwrap_or("*")),
+                ResourceEvent::Error { error } => format!("error {}", error.message()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn coalesces_changes() {
+        let cases: Vec<(&str, Vec<Change>, Vec<&str>)> = vec![
+            (
+                "modified after added stays added",
+                vec![applied("added", "a", "ns"), applied("modified", "a", "ns")],
+                vec!["added a"],
+            ),
+            (
+                "added and deleted within the window",
+                vec![applied("added", "a", "ns"), applied("modified", "a", "ns"), applied("deleted", "a", "ns")],
+                vec![],
+            ),
+            (
+                "latest state of an existing object wins",
+                vec![applied("modified", "a", "ns"), applied("modified", "b", "ns"), applied("deleted", "a", "ns")],
+                vec!["deleted a", "modified b"],
+            ),
+            (
+                "reset drops pending events of its scope only",
+                vec![applied("added", "a", "one"), applied("modified", "b", "two"), reset(Some("one"))],
+                vec!["modified b", "reset one"],
+            ),
+            (
+                "other scopes keep coalescing after a scoped reset",
+                vec![
+                    applied("added", "a", "one"),
+                    applied("added", "b", "two"),
+                    reset(Some("one")),
+                    applied("modified", "b", "two"),
+                    applied("modified", "a", "one"),
+                ],
+                vec!["added b", "reset one", "modified a"],
+            ),
+            (
+                "a full reset drops everything pending",
+                vec![applied("added", "a", "one"), applied("modified", "b", "two"), reset(None), applied("modified", "a", "one")],
+                vec!["reset *", "modified a"],
+            ),
+            (
+                "errors survive a reset",
+                vec![failed("boom"), reset(None)],
+                vec!["error boom", "reset *"],
+            ),
+            (
+                "repeated errors are reported once",
+                vec![failed("boom"), failed("boom"), failed("other"), failed("other")],
+                vec!["error boom", "error other"],
+            ),
+        ];
+
+        for (name, changes, expected) in cases {
+            let mut batch = Batch::default();
+            for change in changes {
+                batch.add(change);
+            }
+            assert_eq!(batch.is_empty(), expected.is_empty(), "{}", name);
+            assert_eq!(describe(batch.take()), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn errors_are_deduplicated_until_a_success() {
+        let mut batch = Batch::default();
+        batch.add(failed("boom"));
+        assert_eq!(describe(batch.take()), vec!["error boom"]);
+
+        // The retry fails the same way in the next window
+        batch.add(failed("boom"));
+        assert!(batch.is_empty());
+
+        batch.add(reset(Some("ns")));
+        batch.add(failed("boom"));
+        assert_eq!(describe(batch.take()), vec!["reset ns", "error boom"]);
+
+        batch.add(applied("modified", "a", "ns"));
+        batch.add(failed("boom"));
+        assert_eq!(describe(batch.take()), vec!["modified a", "error boom"]);
+    }
+}
@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tokio::task::AbortHandle;
+
+use crate::kubernetes::discovery;
+use crate::types::{DiscoveredCluster, KubeconfigChange};
+
+/// How often the kubeconfig files are checked for modifications
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Modification time and size of each kubeconfig file (`None` when missing)
+type Snapshot = Vec<(PathBuf, Option<(SystemTime, u64)>)>;
+
+/// Handle to the background kubeconfig watch task
+#[derive(Default)]
+pub struct KubeconfigWatcher {
+    task: Mutex<Option<AbortHandle>>,
+}
+
+impl KubeconfigWatcher {
+    /// Track a newly spawned watch task, stopping the previous one
+    pub fn replace(&self, task: AbortHandle) {
+        if let Some(previous) = self.task.lock().unwrap().replace(task) {
+            previous.abort();
+        }
+    }
+}
+
+/// Poll the kubeconfig files and report context changes until the task is aborted
+///
+/// Files are polled rather than watched through OS notifications because tools
+/// like `aws eks update-kubeconfig` replace the file instead of writing in place.
+/// A change is only processed once the files have been stable for one interval
+/// so a half-written file isn't reported as every context being removed.
+pub async fn watch_kubeconfigs<F>(extra_paths: Vec<String>, mut on_change: F)
+where
+    F: FnMut(KubeconfigChange),
+{
+    let mut known = discovery::discover_clusters(&extra_paths).await;
+    let mut processed = snapshot(&extra_paths);
+    let mut last_seen = processed.clone();
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let current = snapshot(&extra_paths);
+        if current != last_seen {
+            last_seen = current;
+            continue;
+        }
+        if current == processed {
+            continue;
+        }
+        processed = current;
+
+        let discovered = discovery::discover_clusters(&extra_paths).await;
+        let change = diff_clusters(&known, &discovered);
+        known = discovered;
+
+        if !change.added.is_empty() || !change.removed.is_empty() || !change.changed.is_empty() {
+            on_change(change);
+        }
+    }
+}
+
+/// Record the modification time and size of every kubeconfig file
+fn snapshot(extra_paths: &[String]) -> Snapshot {
+    discovery::get_kubeconfig_paths(extra_paths)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| {
+            let stamp = std::fs::metadata(&path)
+                .ok()
+                .and_then(|m| Some((m.modified().ok()?, m.len())));
+            (path, stamp)
+        })
+        .collect()
+}
+
+/// Compare two discovery results by context name
+fn diff_clusters(previous: &[DiscoveredCluster], current: &[DiscoveredCluster]) -> KubeconfigChange {
+    let find = |list: &[DiscoveredCluster], name: &str| {
+        list.iter().find(|c| c.context_name == name).cloned()
+    };
+
+    KubeconfigChange {
+        added: current
+            .iter()
+            .filter(|c| find(previous, &c.context_name).is_none())
+            .cloned()
+            .collect(),
+        removed: previous
+            .iter()
+            .filter(|c| find(current, &c.context_name).is_none())
+            .cloned()
+            .collect(),
+        changed: current
+            .iter()
+            .filter(|c| find(previous, &c.context_name).is_some_and(|p| &p != *c))
+            .cloned()
+            .collect(),
+    }
+}
@@ -1,85 +1,668 @@
 use chrono::Utc;
-use k8s_openapi::api::core::v1::Pod;
-use kube::api::ListParams;
-use kube::Api;
-
-use crate::types::{Workload, WorkloadsResponse};
-
-/// Get all pods (workloads) from all namespaces
-pub async fn get_workloads(client: kube::Client, context_id: &str) -> WorkloadsResponse {
-    let pods: Api<Pod> = Api::all(client);
-    
-    match pods.list(&ListParams::default()).await {
-        Ok(pod_list) => {
-            let items: Vec<Workload> = pod_list
-                .items
-                .iter()
-                .map(|pod| {
-                    let metadata = &pod.metadata;
-                    let spec = pod.spec.as_ref();
-                    let status = pod.status.as_ref();
-                    
-                    let name = metadata.name.clone().unwrap_or_default();
-                    let namespace = metadata.namespace.clone().unwrap_or_else(|| "default".to_string());
-                    let uid = metadata.uid.clone().unwrap_or_default();
-                    
-                    // Get first container image
-                    let image = spec
-                        .and_then(|s| s.containers.first())
-                        .and_then(|c| c.image.clone())
-                        .unwrap_or_else(|| "unknown".to_string());
-                    
-                    // Get pod status
-                    let phase = status
-                        .and_then(|s| s.phase.clone())
-                        .unwrap_or_else(|| "Unknown".to_string());
-                    
-                    // Calculate uptime
-                    let uptime = status
-                        .and_then(|s| s.start_time.as_ref())
-                        .map(|start_time| {
-                            let start = start_time.0;
-                            let now = Utc::now();
-                            let diff = now.signed_duration_since(start);
-                            
-                            let hours = diff.num_hours();
-                            if hours > 24 {
-                                format!("{}d", hours / 24)
-                            } else {
-                                format!("{}h", hours)
-                            }
-                        })
-                        .unwrap_or_else(|| "0h".to_string());
-                    
-                    Workload {
-                        id: uid,
-                        name,
-                        namespace,
-                        image,
-                        context_id: context_id.to_string(),
-                        status: phase,
-                        replicas: 1,
-                        uptime,
-                    }
-                })
-                .collect();
-            
-            WorkloadsResponse {
-                items,
-                error: None,
-            }
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{Pod, PodSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::error::AppError;
+use crate::kubernetes::listing;
+use crate::kubernetes::pod_status;
+use crate::types::{ListOptions, Workload, WorkloadPod, WorkloadsResponse};
+
+/// Get controllers (Deployments, StatefulSets, DaemonSets, ReplicaSets, Jobs, CronJobs)
+/// each with the pods it owns
+///
+/// ReplicaSets owned by a Deployment and Jobs owned by a CronJob are folded into
+/// their owner. Pods without a controller of those kinds are reported as `Pod`
+/// workloads so nothing running on the cluster is hidden.
+pub async fn get_workloads(client: kube::Client, context_id: &str, options: &ListOptions) -> WorkloadsResponse {
+    match list_workloads(client, context_id, options).await {
+        Ok((items, continue_token)) => WorkloadsResponse {
+            items,
+            continue_token,
+            error: None,
+        },
+        Err(e) => WorkloadsResponse {
+            items: vec![],
+            continue_token: None,
+            error: Some(AppError::from(e)),
+        },
+    }
+}
+
+/// Container reasons that mean a pod won't recover on its own
+const BROKEN_POD_REASONS: &[&str] = &[
+    "CrashLoopBackOff",
+    "ImagePullBackOff",
+    "ErrImagePull",
+    "InvalidImageName",
+    "CreateContainerConfigError",
+    "CreateContainerError",
+    "RunContainerError",
+];
+
+/// Kinds in the order they are paged through; `Pod` covers pods without a controller
+const KINDS: [&str; 7] = ["Deployment", "StatefulSet", "DaemonSet", "ReplicaSet", "Job", "CronJob", "Pod"];
+
+/// Controller kinds whose pods are attached to a workload
+const POD_CONTROLLER_KINDS: [&str; 4] = ["ReplicaSet", "StatefulSet", "DaemonSet", "Job"];
+
+/// Desired and observed replica counts of a controller
+struct Counts {
+    desired: i32,
+    ready: i32,
+    updated: Option<i32>,
+    available: Option<i32>,
+}
+
+/// Objects already listed for the page that can be reused to attach pods
+#[derive(Default)]
+struct Listed {
+    replica_sets: Option<Vec<ReplicaSet>>,
+    jobs: Option<Vec<Job>>,
+    pods: Option<Vec<Pod>>,
+}
+
+/// List one page of workloads, kind by kind, returning the token for the next page
+///
+/// The limit applies to the objects requested from the API server, so a page
+/// can hold fewer workloads once owned ReplicaSets, Jobs and pods are folded in.
+async fn list_workloads(
+    client: kube::Client,
+    context_id: &str,
+    options: &ListOptions,
+) -> Result<(Vec<Workload>, Option<String>), kube::Error> {
+    let (mut kind_index, mut kind_token) = parse_token(options.continue_token.as_deref());
+    // Without selectors or a limit the lists cover everything pods are matched against
+    let reusable = options.limit.is_none() && options.label_selector.is_none() && options.field_selector.is_none();
+
+    let mut workloads = vec![];
+    let mut listed = Listed::default();
+    let mut fetched = 0u32;
+    let mut continue_token = None;
+
+    while kind_index < KINDS.len() {
+        let remaining = options.limit.map(|limit| limit.saturating_sub(fetched));
+        if remaining == Some(0) {
+            continue_token = Some(encode_token(kind_index, None));
+            break;
         }
-        Err(e) => {
-            let error_msg = if e.to_string().contains("connection refused") {
-                "Connection refused. Is the cluster running?".to_string()
-            } else {
-                e.to_string()
-            };
-            
-            WorkloadsResponse {
-                items: vec![],
-                error: Some(error_msg),
+        let kind_options = ListOptions {
+            limit: remaining,
+            continue_token: kind_token.take(),
+            ..options.clone()
+        };
+
+        let next = match KINDS[kind_index] {
+            "Deployment" => {
+                let page = listing::list::<Deployment>(&client, &kind_options).await?;
+                fetched += page.items.len() as u32;
+                workloads.extend(page.items.iter().map(|d| from_deployment(d, context_id)));
+                page.continue_token
+            }
+            "StatefulSet" => {
+                let page = listing::list::<StatefulSet>(&client, &kind_options).await?;
+                fetched += page.items.len() as u32;
+                workloads.extend(page.items.iter().map(|s| from_stateful_set(s, context_id)));
+                page.continue_token
+            }
+            "DaemonSet" => {
+                let page = listing::list::<DaemonSet>(&client, &kind_options).await?;
+                fetched += page.items.len() as u32;
+                workloads.extend(page.items.iter().map(|d| from_daemon_set(d, context_id)));
+                page.continue_token
+            }
+            "ReplicaSet" => {
+                let page = listing::list::<ReplicaSet>(&client, &kind_options).await?;
+                fetched += page.items.len() as u32;
+                workloads.extend(
+                    page.items
+                        .iter()
+                        .filter(|r| !is_controlled_by(&r.metadata, "Deployment"))
+                        .map(|r| from_replica_set(r, context_id)),
+                );
+                if reusable {
+                    listed.replica_sets = Some(page.items);
+                }
+                page.continue_token
+            }
+            "Job" => {
+                let page = listing::list::<Job>(&client, &kind_options).await?;
+                fetched += page.items.len() as u32;
+                workloads.extend(
+                    page.items
+                        .iter()
+                        .filter(|j| !is_controlled_by(&j.metadata, "CronJob"))
+                        .map(|j| from_job(j, context_id)),
+                );
+                if reusable {
+                    listed.jobs = Some(page.items);
+                }
+                page.continue_token
             }
+            "CronJob" => {
+                let page = listing::list::<CronJob>(&client, &kind_options).await?;
+                fetched += page.items.len() as u32;
+                workloads.extend(page.items.iter().map(|c| from_cron_job(c, context_id)));
+                page.continue_token
+            }
+            _ => {
+                let page = listing::list::<Pod>(&client, &kind_options).await?;
+                fetched += page.items.len() as u32;
+                workloads.extend(
+                    page.items
+                        .iter()
+                        .filter(|p| !has_pod_controller(&p.metadata))
+                        .map(|p| bare_pod(p, context_id)),
+                );
+                if reusable {
+                    listed.pods = Some(page.items);
+                }
+                page.continue_token
+            }
+        };
+
+        if let Some(next) = next {
+            continue_token = Some(encode_token(kind_index, Some(next)));
+            break;
+        }
+        kind_index += 1;
+    }
+
+    attach_pods(&client, &mut workloads, options, listed).await?;
+
+    for item in &mut workloads {
+        if item.kind == "CronJob" {
+            item.ready_replicas = item.pods.iter().filter(|p| p.ready).count() as i32;
+        }
+        if let Some(pod) = item.pods.iter().find(|p| is_broken(&p.status)) {
+            item.status = pod.status.clone();
+        }
+        item.pods.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    workloads.sort_by(|a, b| (&a.namespace, &a.name, &a.kind).cmp(&(&b.namespace, &b.name, &b.kind)));
+    Ok((workloads, continue_token))
+}
+
+/// Attach pods to the controllers on the page through their ownerReferences
+///
+/// Pods, ReplicaSets and Jobs are listed without the caller's selectors, since a
+/// controller's pods needn't carry its labels, and only in the namespaces the
+/// page's controllers live in when the request was narrowed down.
+async fn attach_pods(
+    client: &kube::Client,
+    workloads: &mut [Workload],
+    options: &ListOptions,
+    listed: Listed,
+) -> Result<(), kube::Error> {
+    let namespaces: BTreeSet<String> = workloads
+        .iter()
+        .filter(|w| w.kind != "Pod")
+        .map(|w| w.namespace.clone())
+        .collect();
+    if namespaces.is_empty() {
+        return Ok(());
+    }
+
+    let unfiltered = options.limit.is_none() && options.label_selector.is_none() && options.field_selector.is_none();
+    let scope = ListOptions {
+        namespaces: if unfiltered {
+            options.namespaces.clone()
+        } else {
+            namespaces.into_iter().collect()
+        },
+        ..Default::default()
+    };
+
+    let replica_sets = match listed.replica_sets {
+        Some(items) => items,
+        None => listing::list::<ReplicaSet>(client, &scope).await?.items,
+    };
+    let jobs = match listed.jobs {
+        Some(items) => items,
+        None => listing::list::<Job>(client, &scope).await?.items,
+    };
+    let pods = match listed.pods {
+        Some(items) => items,
+        None => listing::list::<Pod>(client, &scope).await?.items,
+    };
+
+    let rolled_up = rolled_up(&replica_sets, &jobs);
+
+    let index: HashMap<String, usize> = workloads
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| w.kind != "Pod")
+        .map(|(i, w)| (w.id.clone(), i))
+        .collect();
+
+    for pod in &pods {
+        let Some(owner) = controller_uid(&pod.metadata) else { continue };
+        let owner = rolled_up.get(&owner).cloned().unwrap_or(owner);
+        if let Some(&i) = index.get(&owner) {
+            workloads[i].pods.push(workload_pod(pod));
+        }
+    }
+
+    Ok(())
+}
+
+fn from_deployment(deployment: &Deployment, context_id: &str) -> Workload {
+    let spec = deployment.spec.as_ref();
+    let status = deployment.status.clone().unwrap_or_default();
+    let counts = Counts {
+        desired: spec.and_then(|s| s.replicas).unwrap_or(1),
+        ready: status.ready_replicas.unwrap_or(0),
+        updated: Some(status.updated_replicas.unwrap_or(0)),
+        available: Some(status.available_replicas.unwrap_or(0)),
+    };
+    workload(
+        "Deployment",
+        &deployment.metadata,
+        spec.and_then(|s| s.template.spec.as_ref()),
+        spec.map(|s| &s.selector),
+        counts,
+        context_id,
+    )
+}
+
+fn from_stateful_set(stateful_set: &StatefulSet, context_id: &str) -> Workload {
+    let spec = stateful_set.spec.as_ref();
+    let status = stateful_set.status.clone().unwrap_or_default();
+    let counts = Counts {
+        desired: spec.and_then(|s| s.replicas).unwrap_or(1),
+        ready: status.ready_replicas.unwrap_or(0),
+        updated: Some(status.updated_replicas.unwrap_or(0)),
+        available: Some(status.available_replicas.unwrap_or(0)),
+    };
+    workload(
+        "StatefulSet",
+        &stateful_set.metadata,
+        spec.and_then(|s| s.template.spec.as_ref()),
+        spec.map(|s| &s.selector),
+        counts,
+        context_id,
+    )
+}
+
+fn from_daemon_set(daemon_set: &DaemonSet, context_id: &str) -> Workload {
+    let spec = daemon_set.spec.as_ref();
+    let status = daemon_set.status.clone().unwrap_or_default();
+    let counts = Counts {
+        desired: status.desired_number_scheduled,
+        ready: status.number_ready,
+        updated: Some(status.updated_number_scheduled.unwrap_or(0)),
+        available: Some(status.number_available.unwrap_or(0)),
+    };
+    workload(
+        "DaemonSet",
+        &daemon_set.metadata,
+        spec.and_then(|s| s.template.spec.as_ref()),
+        spec.map(|s| &s.selector),
+        counts,
+        context_id,
+    )
+}
+
+fn from_replica_set(replica_set: &ReplicaSet, context_id: &str) -> Workload {
+    let spec = replica_set.spec.as_ref();
+    let status = replica_set.status.clone().unwrap_or_default();
+    let counts = Counts {
+        desired: spec.and_then(|s| s.replicas).unwrap_or(1),
+        ready: status.ready_replicas.unwrap_or(0),
+        updated: None,
+        available: Some(status.available_replicas.unwrap_or(0)),
+    };
+    workload(
+        "ReplicaSet",
+        &replica_set.metadata,
+        spec.and_then(|s| s.template.as_ref()).and_then(|t| t.spec.as_ref()),
+        spec.map(|s| &s.selector),
+        counts,
+        context_id,
+    )
+}
+
+fn from_job(job: &Job, context_id: &str) -> Workload {
+    let spec = job.spec.as_ref();
+    let status = job.status.clone().unwrap_or_default();
+    // For Jobs, "available" is the number of completions reached
+    let counts = Counts {
+        desired: spec.and_then(|s| s.completions).unwrap_or(1),
+        ready: status.ready.unwrap_or(0),
+        updated: None,
+        available: Some(status.succeeded.unwrap_or(0)),
+    };
+    let mut item = workload(
+        "Job",
+        &job.metadata,
+        spec.map(|s| &s.template).and_then(|t| t.spec.as_ref()),
+        spec.and_then(|s| s.selector.as_ref()),
+        counts,
+        context_id,
+    );
+    item.status = job_status(job);
+    item
+}
+
+fn from_cron_job(cron_job: &CronJob, context_id: &str) -> Workload {
+    let spec = cron_job.spec.as_ref();
+    let status = cron_job.status.clone().unwrap_or_default();
+    // For CronJobs, the desired count is the number of Jobs currently running
+    let active = status.active.map(|a| a.len() as i32).unwrap_or(0);
+    let counts = Counts {
+        desired: active,
+        ready: 0,
+        updated: None,
+        available: None,
+    };
+    let mut item = workload(
+        "CronJob",
+        &cron_job.metadata,
+        spec.and_then(|s| s.job_template.spec.as_ref())
+            .and_then(|s| s.template.spec.as_ref()),
+        None,
+        counts,
+        context_id,
+    );
+    item.status = if spec.and_then(|s| s.suspend).unwrap_or(false) {
+        "Suspended".to_string()
+    } else if active > 0 {
+        "Running".to_string()
+    } else {
+        "Scheduled".to_string()
+    };
+    item
+}
+
+/// Uid of an intermediate controller -> uid of the workload it rolls up into
+///
+/// Only ReplicaSets of a Deployment and Jobs of a CronJob roll up; those of
+/// other controllers, such as an Argo Rollout, are workloads of their own.
+fn rolled_up(replica_sets: &[ReplicaSet], jobs: &[Job]) -> HashMap<String, String> {
+    let replica_sets = replica_sets
+        .iter()
+        .map(|r| &r.metadata)
+        .filter(|meta| is_controlled_by(meta, "Deployment"));
+    let jobs = jobs
+        .iter()
+        .map(|j| &j.metadata)
+        .filter(|meta| is_controlled_by(meta, "CronJob"));
+
+    replica_sets
+        .chain(jobs)
+        .filter_map(|meta| Some((meta.uid.clone()?, controller_uid(meta)?)))
+        .collect()
+}
+
+/// Whether an object's controller is of the given kind
+fn is_controlled_by(metadata: &ObjectMeta, kind: &str) -> bool {
+    metadata
+        .owner_references
+        .iter()
+        .flatten()
+        .any(|o| o.controller == Some(true) && o.kind == kind)
+}
+
+/// Whether a pod belongs to a controller it is shown under rather than on its own
+fn has_pod_controller(metadata: &ObjectMeta) -> bool {
+    metadata
+        .owner_references
+        .iter()
+        .flatten()
+        .any(|o| o.controller == Some(true) && POD_CONTROLLER_KINDS.contains(&o.kind.as_str()))
+}
+
+/// Token of the form `<kind index>:<continue token of that kind>`
+fn encode_token(kind_index: usize, token: Option<String>) -> String {
+    format!("{}:{}", kind_index, token.unwrap_or_default())
+}
+
+fn parse_token(token: Option<&str>) -> (usize, Option<String>) {
+    token
+        .and_then(|t| t.split_once(':'))
+        .and_then(|(index, rest)| {
+            let index = index.parse().ok()?;
+            Some((index, Some(rest.to_string()).filter(|t| !t.is_empty())))
+        })
+        .unwrap_or((0, None))
+}
+
+/// Pod statuses that need attention and are surfaced on the owning workload
+fn is_broken(status: &str) -> bool {
+    let reason = status.strip_prefix("Init:").unwrap_or(status);
+    BROKEN_POD_REASONS.contains(&reason)
+}
+
+/// Build a workload from a controller's metadata, pod template and replica counts
+fn workload(
+    kind: &str,
+    metadata: &ObjectMeta,
+    pod_spec: Option<&PodSpec>,
+    selector: Option<&LabelSelector>,
+    counts: Counts,
+    context_id: &str,
+) -> Workload {
+    let images = images(pod_spec);
+    let status = if counts.desired == 0 {
+        "ScaledDown"
+    } else if counts.ready >= counts.desired {
+        "Running"
+    } else if counts.ready == 0 {
+        "Pending"
+    } else {
+        "Degraded"
+    };
+
+    Workload {
+        id: metadata.uid.clone().unwrap_or_default(),
+        name: metadata.name.clone().unwrap_or_default(),
+        namespace: metadata.namespace.clone().unwrap_or_else(|| "default".to_string()),
+        kind: kind.to_string(),
+        image: images.first().cloned().unwrap_or_else(|| "unknown".to_string()),
+        images,
+        context_id: context_id.to_string(),
+        status: status.to_string(),
+        replicas: counts.desired,
+        ready_replicas: counts.ready,
+        updated_replicas: counts.updated,
+        available_replicas: counts.available,
+        selector: selector.map(format_selector),
+        pods: vec![],
+        uptime: pod_status::age(metadata.creation_timestamp.as_ref()),
+    }
+}
+
+/// A pod without a listed controller, shown as a workload of its own
+fn bare_pod(pod: &Pod, context_id: &str) -> Workload {
+    let summary = workload_pod(pod);
+    let mut item = workload(
+        "Pod",
+        &pod.metadata,
+        pod.spec.as_ref(),
+        None,
+        Counts {
+            desired: 1,
+            ready: summary.ready as i32,
+            updated: None,
+            available: None,
+        },
+        context_id,
+    );
+    item.status = summary.status.clone();
+    item.pods.push(summary);
+    item
+}
+
+fn workload_pod(pod: &Pod) -> WorkloadPod {
+    let summary = pod_status::summarize(pod);
+    let last_termination = summary.last_termination.as_ref();
+
+    WorkloadPod {
+        name: pod.metadata.name.clone().unwrap_or_default(),
+        ready: pod
+            .status
+            .as_ref()
+            .and_then(|s| s.conditions.as_ref())
+            .and_then(|conditions| conditions.iter().find(|c| c.type_ == "Ready"))
+            .is_some_and(|c| c.status == "True"),
+        ready_containers: format!("{}/{}", summary.ready_containers, summary.total_containers),
+        restarts: summary.restarts,
+        last_restart: summary
+            .last_restart
+            .map(|t| pod_status::human_duration(Utc::now().signed_duration_since(t))),
+        last_termination_reason: last_termination.and_then(|t| t.reason.clone()),
+        last_exit_code: last_termination.map(|t| t.exit_code),
+        status: summary.status,
+        node_name: pod.spec.as_ref().and_then(|s| s.node_name.clone()),
+        age: pod_status::age(pod.metadata.creation_timestamp.as_ref()),
+    }
+}
+
+fn job_status(job: &Job) -> String {
+    let finished = job
+        .status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .and_then(|conditions| {
+            conditions
+                .iter()
+                .find(|c| (c.type_ == "Complete" || c.type_ == "Failed") && c.status == "True")
+        });
+
+    match finished {
+        Some(c) if c.type_ == "Complete" => "Completed".to_string(),
+        Some(_) => "Failed".to_string(),
+        None => "Running".to_string(),
+    }
+}
+
+/// Uid of the controller that owns an object
+pub fn controller_uid(metadata: &ObjectMeta) -> Option<String> {
+    metadata
+        .owner_references
+        .as_ref()?
+        .iter()
+        .find(|o| o.controller == Some(true))
+        .map(|o| o.uid.clone())
+}
+
+/// Images of all containers in a pod template, init containers first
+pub fn images(pod_spec: Option<&PodSpec>) -> Vec<String> {
+    let Some(spec) = pod_spec else { return vec![] };
+    let mut images: Vec<String> = vec![];
+    for image in spec
+        .init_containers
+        .iter()
+        .flatten()
+        .chain(&spec.containers)
+        .filter_map(|c| c.image.as_ref())
+    {
+        if !images.contains(image) {
+            images.push(image.clone());
+        }
+    }
+    images
+}
+
+/// Render a label selector the way kubectl prints it, e.g. `app=web,tier in (a,b)`
+pub fn format_selector(selector: &LabelSelector) -> String {
+    let labels = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(k, v)| format!("{}={}", k, v));
+    let expressions = selector.match_expressions.iter().flatten().map(|e| {
+        let values = e.values.as_deref().unwrap_or_default().join(",");
+        match e.operator.as_str() {
+            "In" => format!("{} in ({})", e.key, values),
+            "NotIn" => format!("{} notin ({})", e.key, values),
+            "Exists" => e.key.clone(),
+            "DoesNotExist" => format!("!{}", e.key),
+            other => format!("{} {} ({})", e.key, other, values),
         }
+    });
+
+    labels.chain(expressions).collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn continue_tokens() {
+        let cases = [
+            (None, (0, None)),
+            (Some(""), (0, None)),
+            (Some("garbage"), (0, None)),
+            (Some("x:abc"), (0, None)),
+            (Some("2:"), (2, None)),
+            (Some("1:abc"), (1, Some("abc"))),
+            // Continue tokens are opaque and may contain the separator themselves
+            (Some("3:a:b:c"), (3, Some("a:b:c"))),
+        ];
+
+        for (token, (index, inner)) in cases {
+            assert_eq!(parse_token(token), (index, inner.map(str::to_string)), "{:?}", token);
+        }
+
+        for (index, inner) in [(0, None), (4, Some("eyJ2Ijoi:bWV0YS")), (5, Some("abc"))] {
+            let token = encode_token(index, inner.map(str::to_string));
+            assert_eq!(parse_token(Some(&token)), (index, inner.map(str::to_string)), "{}", token);
+        }
+    }
+
+    fn owned(uid: &str, kind: &str, owner_uid: &str) -> ObjectMeta {
+        serde_json::from_value(json!({
+            "name": uid,
+            "uid": uid,
+            "ownerReferences": [{ "apiVersion": "v1", "kind": kind, "name": owner_uid, "uid": owner_uid, "controller": true }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn only_deployment_and_cron_job_children_roll_up() {
+        let replica_sets = [
+            ReplicaSet { metadata: owned("rs-web", "Deployment", "web"), ..Default::default() },
+            ReplicaSet { metadata: owned("rs-canary", "Rollout", "canary"), ..Default::default() },
+            ReplicaSet { metadata: ObjectMeta { uid: Some("rs-bare".to_string()), ..Default::default() }, ..Default::default() },
+        ];
+        let jobs = [
+            Job { metadata: owned("job-nightly", "CronJob", "nightly"), ..Default::default() },
+            Job { metadata: owned("job-backup", "Backup", "backup"), ..Default::default() },
+        ];
+
+        let rolled_up = rolled_up(&replica_sets, &jobs);
+        assert_eq!(rolled_up.get("rs-web").map(String::as_str), Some("web"));
+        assert_eq!(rolled_up.get("job-nightly").map(String::as_str), Some("nightly"));
+        assert_eq!(rolled_up.len(), 2);
+
+        // Listed as workloads of their own, so their pods attach to them directly
+        assert!(!is_controlled_by(&replica_sets[1].metadata, "Deployment"));
+        assert!(!is_controlled_by(&replica_sets[2].metadata, "Deployment"));
+        assert!(!is_controlled_by(&jobs[1].metadata, "CronJob"));
+        assert!(is_controlled_by(&replica_sets[0].metadata, "Deployment"));
+    }
+
+    #[test]
+    fn images_are_deduplicated_in_order() {
+        let spec: PodSpec = serde_json::from_value(json!({
+            "initContainers": [{ "name": "migrate", "image": "app:2" }],
+            "containers": [
+                { "name": "proxy", "image": "envoy:1" },
+                { "name": "app", "image": "app:2" },
+                { "name": "sidecar", "image": "envoy:1" },
+                { "name": "no-image" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(images(Some(&spec)), vec!["app:2", "envoy:1"]);
+        assert!(images(None).is_empty());
     }
 }
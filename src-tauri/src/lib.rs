@@ -1,4 +1,5 @@
 mod commands;
+mod error;
 mod kubernetes;
 mod persistence;
 mod types;
@@ -6,6 +7,10 @@ mod types;
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
+    .manage(kubernetes::pool::ClientPool::default())
+    .manage(kubernetes::watcher::KubeconfigWatcher::default())
+    .manage(kubernetes::drain::DrainTasks::default())
+    .manage(kubernetes::subscriptions::Subscriptions::default())
     .setup(|app| {
       if cfg!(debug_assertions) {
         app.handle().plugin(
@@ -14,17 +19,44 @@ pub fn run() {
             .build(),
         )?;
       }
+      commands::start_kubeconfig_watch(app.handle().clone(), vec![]);
       Ok(())
     })
     .invoke_handler(tauri::generate_handler![
       commands::get_cluster_info,
+      commands::get_nodes,
+      commands::cordon_node,
+      commands::uncordon_node,
+      commands::drain_node,
+      commands::cancel_drain,
       commands::get_workloads,
+      commands::scale_workload,
+      commands::rollout_restart,
+      commands::rollout_status,
+      commands::rollout_history,
+      commands::rollout_undo,
       commands::get_resources,
+      commands::delete_resource,
+      commands::subscribe_resources,
+      commands::unsubscribe_resources,
+      commands::diagnose_cluster,
       commands::save_clusters,
       commands::load_clusters,
       commands::discover_clusters,
+      commands::probe_discovered_clusters,
+      commands::import_discovered_cluster,
+      commands::rename_kubeconfig_context,
+      commands::delete_kubeconfig_context,
+      commands::set_kubeconfig_context_namespace,
+      commands::use_kubeconfig_context,
+      commands::watch_kubeconfigs,
+      commands::list_kubeconfig_contexts,
       commands::exec_pod_command,
       commands::get_pod_containers,
+      commands::get_pod_logs,
+      commands::follow_pod_logs,
+      commands::tail_pod_logs,
+      commands::stop_pod_logs,
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::AppError;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClusterContext {
     pub id: String,
@@ -15,19 +17,233 @@ pub struct ClusterContext {
     #[serde(rename = "nodeCount")]
     pub node_count: usize,
     pub kubeconfig: Option<String>,
+    /// Context to use from the kubeconfig instead of its `current-context`
+    #[serde(rename = "contextName", skip_serializing_if = "Option::is_none")]
+    pub context_name: Option<String>,
+    /// Cluster entry overriding the one named by the context
+    #[serde(rename = "clusterName", skip_serializing_if = "Option::is_none")]
+    pub cluster_name: Option<String>,
+    /// User entry overriding the one named by the context
+    #[serde(rename = "userName", skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+}
+
+/// Where the CPU and memory percentages in `ClusterInfo` came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageSource {
+    /// Live usage from metrics-server (metrics.k8s.io)
+    Metrics,
+    /// Summed pod requests, used when metrics-server isn't installed
+    Requests,
+    Unavailable,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClusterInfo {
     pub status: String,
+    /// Why the status is not Healthy
+    #[serde(rename = "statusReasons")]
+    pub status_reasons: Vec<String>,
     #[serde(rename = "nodeCount")]
     pub node_count: usize,
     #[serde(rename = "cpuUsage")]
     pub cpu_usage: f64,
     #[serde(rename = "memUsage")]
     pub mem_usage: f64,
+    #[serde(rename = "usageSource")]
+    pub usage_source: UsageSource,
+    #[serde(flatten)]
+    pub platform: PlatformInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<AppError>,
+}
+
+/// Kubernetes distribution, detected from the server version and node metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Distribution {
+    Eks,
+    Gke,
+    Aks,
+    K3s,
+    Kind,
+    OpenShift,
+    /// Upstream Kubernetes or a distribution we don't recognise
+    Kubernetes,
+}
+
+/// Operating system and architecture shared by a group of nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodePlatform {
+    pub os: String,
+    pub architecture: String,
+    pub count: usize,
+}
+
+/// API server version and the software running on the nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlatformInfo {
+    /// `gitVersion` from `/version`
+    #[serde(rename = "serverVersion", skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+    /// `platform` from `/version`, e.g. linux/amd64
+    #[serde(rename = "serverPlatform", skip_serializing_if = "Option::is_none")]
+    pub server_platform: Option<String>,
+    /// Distinct kubelet versions, oldest first
+    #[serde(rename = "kubeletVersions")]
+    pub kubelet_versions: Vec<String>,
+    /// Distinct container runtime versions, e.g. containerd://1.7.11
+    #[serde(rename = "containerRuntimes")]
+    pub container_runtimes: Vec<String>,
+    #[serde(rename = "nodePlatforms")]
+    pub node_platforms: Vec<NodePlatform>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distribution: Option<Distribution>,
+    /// Set when the server version is outside the API version the app is built against
+    #[serde(rename = "compatibilityWarning", skip_serializing_if = "Option::is_none")]
+    pub compatibility_warning: Option<String>,
+}
+
+/// CPU in cores, memory and ephemeral storage in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeResources {
+    pub cpu: f64,
+    pub memory: f64,
+    pub pods: f64,
+    #[serde(rename = "ephemeralStorage")]
+    pub ephemeral_storage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeTaint {
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub effect: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeCondition {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(rename = "lastTransitionTime", skip_serializing_if = "Option::is_none")]
+    pub last_transition_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeDetails {
+    pub name: String,
+    pub roles: Vec<String>,
+    #[serde(rename = "internalIps")]
+    pub internal_ips: Vec<String>,
+    #[serde(rename = "externalIps")]
+    pub external_ips: Vec<String>,
+    #[serde(rename = "instanceType", skip_serializing_if = "Option::is_none")]
+    pub instance_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(rename = "kubeletVersion")]
+    pub kubelet_version: String,
+    pub capacity: NodeResources,
+    pub allocatable: NodeResources,
+    pub taints: Vec<NodeTaint>,
+    pub conditions: Vec<NodeCondition>,
+    pub unschedulable: bool,
+    /// Non-terminated pods on the node; unset when pods can't be listed
+    #[serde(rename = "podCount", skip_serializing_if = "Option::is_none")]
+    pub pod_count: Option<usize>,
+    #[serde(rename = "creationTimestamp")]
+    pub creation_timestamp: String,
+}
+
+/// Options for draining a node, mirroring the `kubectl drain` flags
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DrainOptions {
+    /// Evict pods with emptyDir volumes, losing their data
+    #[serde(rename = "deleteEmptyDirData", default)]
+    pub delete_empty_dir_data: bool,
+    /// Evict pods that no controller will recreate
+    #[serde(default)]
+    pub force: bool,
+    /// Overrides each pod's terminationGracePeriodSeconds
+    #[serde(rename = "gracePeriodSeconds", default)]
+    pub grace_period_seconds: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DrainPodStatus {
+    /// DaemonSet or mirror pod, left on the node
+    Skipped,
+    Evicting,
+    /// A PodDisruptionBudget refused the eviction; it is retried
+    Blocked,
+    Evicted,
+    Failed,
+}
+
+/// Progress of one pod during a drain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrainProgress {
+    pub pod: String,
+    pub namespace: String,
+    pub status: DrainPodStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DrainSummary {
+    pub evicted: usize,
+    pub skipped: usize,
+}
+
+/// Scope, filters and page for list commands
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListOptions {
+    /// Namespaces to list in; empty means all namespaces
+    pub namespaces: Vec<String>,
+    #[serde(rename = "labelSelector", skip_serializing_if = "Option::is_none")]
+    pub label_selector: Option<String>,
+    #[serde(rename = "fieldSelector", skip_serializing_if = "Option::is_none")]
+    pub field_selector: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+    pub limit: Option<u32>,
+    /// Token from the previous page's response
+    #[serde(rename = "continue", skip_serializing_if = "Option::is_none")]
+    pub continue_token: Option<String>,
+}
+
+/// A pod owned by a workload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadPod {
+    pub name: String,
+    /// Status as `kubectl get pods` shows it, e.g. CrashLoopBackOff or Init:1/2
+    pub status: String,
+    pub ready: bool,
+    /// Ready and total containers, e.g. 2/3
+    #[serde(rename = "readyContainers")]
+    pub ready_containers: String,
+    pub restarts: i32,
+    /// Time since the last container restart, e.g. 5m12s
+    #[serde(rename = "lastRestart", skip_serializing_if = "Option::is_none")]
+    pub last_restart: Option<String>,
+    #[serde(rename = "lastTerminationReason", skip_serializing_if = "Option::is_none")]
+    pub last_termination_reason: Option<String>,
+    #[serde(rename = "lastExitCode", skip_serializing_if = "Option::is_none")]
+    pub last_exit_code: Option<i32>,
+    #[serde(rename = "nodeName", skip_serializing_if = "Option::is_none")]
+    pub node_name: Option<String>,
+    pub age: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,19 +251,100 @@ pub struct Workload {
     pub id: String,
     pub name: String,
     pub namespace: String,
+    /// Deployment, StatefulSet, DaemonSet, ReplicaSet, Job, CronJob or Pod
+    pub kind: String,
+    /// First container image, kept for views that show a single image
     pub image: String,
+    pub images: Vec<String>,
     #[serde(rename = "contextId")]
     pub context_id: String,
     pub status: String,
+    /// Desired replicas
     pub replicas: i32,
+    #[serde(rename = "readyReplicas")]
+    pub ready_replicas: i32,
+    #[serde(rename = "updatedReplicas", skip_serializing_if = "Option::is_none")]
+    pub updated_replicas: Option<i32>,
+    #[serde(rename = "availableReplicas", skip_serializing_if = "Option::is_none")]
+    pub available_replicas: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    pub pods: Vec<WorkloadPod>,
     pub uptime: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScaleRequest {
+    /// Deployment, StatefulSet or ReplicaSet
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub replicas: i32,
+    /// Validate on the server without changing anything
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+    /// Scale even though an HPA manages the target
+    #[serde(rename = "ignoreHpa", default)]
+    pub ignore_hpa: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScaleResult {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    /// Replica count before the change, to scale back to on undo
+    #[serde(rename = "previousReplicas")]
+    pub previous_replicas: i32,
+    pub replicas: i32,
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutStatus {
+    /// Progress as `kubectl rollout status` words it
+    pub message: String,
+    pub done: bool,
+    #[serde(rename = "desiredReplicas")]
+    pub desired_replicas: i32,
+    #[serde(rename = "updatedReplicas")]
+    pub updated_replicas: i32,
+    #[serde(rename = "readyReplicas")]
+    pub ready_replicas: i32,
+    #[serde(rename = "availableReplicas")]
+    pub available_replicas: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutRevision {
+    pub revision: i64,
+    /// ReplicaSet or ControllerRevision holding the revision
+    pub name: String,
+    #[serde(rename = "changeCause")]
+    pub change_cause: Option<String>,
+    pub images: Vec<String>,
+    pub created: Option<String>,
+    /// Whether this is the revision currently rolled out
+    pub current: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutUndo {
+    /// Revision rolled back to
+    pub revision: i64,
+    /// The controller already ran that revision, so nothing changed
+    pub skipped: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkloadsResponse {
     pub items: Vec<Workload>,
+    /// Pass back in `ListOptions` to fetch the next page
+    #[serde(rename = "continue", skip_serializing_if = "Option::is_none")]
+    pub continue_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+    pub error: Option<AppError>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,33 +356,182 @@ pub struct Resource {
     pub creation_timestamp: String,
 }
 
+/// What happens to the dependents of a deleted object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeletePropagation {
+    /// Dependents are deleted before the owner
+    Foreground,
+    /// The owner is deleted at once and dependents are cleaned up afterwards
+    Background,
+    /// Dependents are kept, with their owner reference removed
+    Orphan,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteRequest {
+    /// Any type `get_resources` supports, e.g. "Pods" or "Deployments"
+    #[serde(rename = "resourceType")]
+    pub resource_type: String,
+    /// Required for namespaced types
+    #[serde(default)]
+    pub namespace: Option<String>,
+    pub names: Vec<String>,
+    #[serde(rename = "gracePeriodSeconds", default)]
+    pub grace_period_seconds: Option<u32>,
+    #[serde(rename = "propagationPolicy", default)]
+    pub propagation_policy: Option<DeletePropagation>,
+    /// Delete Pods immediately, without waiting for the kubelet to confirm
+    #[serde(default)]
+    pub force: bool,
+    /// Validate on the server and list dependents without deleting anything
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeleteStatus {
+    Deleted,
+    /// Deletion started but finalizers or a grace period keep the object around
+    Terminating,
+    /// Dry run: the object would be deleted
+    DryRun,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteDependent {
+    pub kind: String,
+    pub name: String,
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteResult {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub status: DeleteStatus,
+    /// Objects the deletion would cascade to; only filled for dry runs
+    pub dependents: Vec<DeleteDependent>,
+    pub error: Option<AppError>,
+}
+
+/// Change to a watched resource, sent in batches to subscribers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ResourceEvent {
+    Added { object: Resource },
+    Modified { object: Resource },
+    Deleted { object: Resource },
+    /// Full state after a (re)list, replacing everything received so far for
+    /// the namespace, or for the whole subscription when no namespace is set
+    Reset {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        namespace: Option<String>,
+        items: Vec<Resource>,
+    },
+    Error { error: AppError },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourcesResponse {
     pub items: Vec<Resource>,
+    /// Pass back in `ListOptions` to fetch the next page
+    #[serde(rename = "continue", skip_serializing_if = "Option::is_none")]
+    pub continue_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+    pub error: Option<AppError>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ProviderType {
     Local,
     Aws,
     Azure,
     Gcp,
+    OpenShift,
+    Rancher,
+    DigitalOcean,
+    Kind,
+    Minikube,
+    K3d,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ProviderType {
+    /// Provider id as used by the frontend
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderType::Local => "local",
+            ProviderType::Aws => "aws",
+            ProviderType::Azure => "azure",
+            ProviderType::Gcp => "gcp",
+            ProviderType::OpenShift => "openshift",
+            ProviderType::Rancher => "rancher",
+            ProviderType::DigitalOcean => "digitalocean",
+            ProviderType::Kind => "kind",
+            ProviderType::Minikube => "minikube",
+            ProviderType::K3d => "k3d",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DiscoveredCluster {
     #[serde(rename = "contextName")]
     pub context_name: String,
     #[serde(rename = "clusterName")]
     pub cluster_name: String,
+    #[serde(rename = "userName")]
+    pub user_name: String,
     pub server: String,
     pub namespace: String,
     #[serde(rename = "isCurrentContext")]
     pub is_current_context: bool,
     pub provider: ProviderType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// AWS account id or GCP project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Cluster name as known to the provider
+    #[serde(rename = "providerClusterName", skip_serializing_if = "Option::is_none")]
+    pub provider_cluster_name: Option<String>,
+    /// Kubeconfig file the context was read from; absent for pasted kubeconfigs
+    #[serde(rename = "sourceFile", skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuthOutcome {
+    Authenticated,
+    Rejected,
+    PluginFailed,
+    NotChecked,
+}
+
+/// Result of checking whether a discovered context can be reached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterProbe {
+    #[serde(rename = "contextName")]
+    pub context_name: String,
+    pub reachable: bool,
+    #[serde(rename = "serverVersion")]
+    pub server_version: Option<String>,
+    pub auth: AuthOutcome,
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<AppError>,
+}
+
+/// Payload of the `kubeconfig-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubeconfigChange {
+    pub added: Vec<DiscoveredCluster>,
+    pub removed: Vec<DiscoveredCluster>,
+    pub changed: Vec<DiscoveredCluster>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,5 +547,116 @@ pub struct ExecRequest {
 pub struct ExecResponse {
     pub output: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+    pub error: Option<AppError>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogOptions {
+    /// Defaults to the pod's default container
+    pub container: Option<String>,
+    #[serde(rename = "tailLines")]
+    pub tail_lines: Option<i64>,
+    #[serde(rename = "sinceSeconds")]
+    pub since_seconds: Option<i64>,
+    /// RFC 3339 timestamp; exclusive with `since_seconds`
+    #[serde(rename = "sinceTime")]
+    pub since_time: Option<String>,
+    pub timestamps: bool,
+    /// Log of the previous, terminated run of the container
+    pub previous: bool,
+    #[serde(rename = "limitBytes")]
+    pub limit_bytes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodLogs {
+    /// Container the log was read from
+    pub container: String,
+    pub logs: String,
+    /// The byte limit cut the log short
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLine {
+    pub pod: String,
+    pub container: String,
+    /// When the container wrote the line, as recorded by the kubelet
+    pub timestamp: Option<String>,
+    pub message: String,
+}
+
+/// Pods whose logs are followed together, stern-style
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogTailRequest {
+    pub namespace: String,
+    /// Either a label selector or a Deployment whose selector is used
+    #[serde(rename = "labelSelector", default)]
+    pub label_selector: Option<String>,
+    #[serde(default)]
+    pub deployment: Option<String>,
+    /// Regexes; when any are given, only lines matching one are sent
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Regexes; lines matching any of them are dropped
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// Update from a followed log, sent to subscribers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LogEvent {
+    Lines { lines: Vec<LogLine> },
+    /// A container's log is now being followed
+    Started { pod: String, container: String },
+    /// The container restarted and its new log is being followed
+    Restarted {
+        pod: String,
+        container: String,
+        #[serde(rename = "restartCount")]
+        restart_count: i32,
+    },
+    /// The log won't grow any more, e.g. because the pod was deleted
+    Ended { pod: String, container: String, reason: String },
+    /// Opening the log, or finding pods to follow, failed; it is retried
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pod: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        container: Option<String>,
+        error: AppError,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticStep {
+    pub id: String,
+    pub label: String,
+    pub status: DiagnosticStatus,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<AppError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticReport {
+    pub server: Option<String>,
+    pub steps: Vec<DiagnosticStep>,
+    #[serde(rename = "failedStep")]
+    pub failed_step: Option<String>,
+    #[serde(rename = "totalMs")]
+    pub total_ms: u64,
 }
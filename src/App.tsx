@@ -112,7 +112,7 @@ export default function App() {
     } else if (cmd === 'kubectl get pods') {
       newHistory.push('NAME            READY   STATUS    RESTARTS   AGE');
       visibleWorkloads.forEach(w => {
-        newHistory.push(`${w.name.padEnd(15)} ${w.replicas}/${w.replicas}     ${w.status.padEnd(9)} 0          ${w.uptime}`);
+        newHistory.push(`${w.name.padEnd(15)} ${w.readyReplicas}/${w.replicas}     ${w.status.padEnd(9)} 0          ${w.uptime}`);
       });
     } else if (cmd.startsWith('kubectl edit pod')) {
       const podName = cmd.split(' ')[3];
@@ -173,13 +173,14 @@ export default function App() {
         id: `discovered-${d.contextName}-${Date.now()}`,
         name: d.contextName,
         provider: d.provider,
-        region: 'auto-discovered',
+        region: d.region ?? 'auto-discovered',
         environment: d.isCurrentContext ? 'dev' : 'local',
         status: 'Connecting...',
         cpuUsage: 0,
         memUsage: 0,
         nodeCount: 0,
         kubeconfig: undefined, // Will use default kubeconfig
+        contextName: d.contextName,
       }));
 
       // Combine and deduplicate everything
@@ -211,8 +212,15 @@ export default function App() {
   const handleExecPod = async (workload: Workload) => {
     if (!activeContext) return;
 
+    const podName = workload.pods[0]?.name;
+    if (!podName) {
+      setTerminalHistory(prev => [...prev, `Error: ${workload.kind} ${workload.name} has no pods`]);
+      setIsTerminalOpen(true);
+      return;
+    }
+
     setIsTerminalOpen(true);
-    setTerminalHistory(prev => [...prev, `➜ ~ Connecting to pod ${workload.name}...`]);
+    setTerminalHistory(prev => [...prev, `➜ ~ Connecting to pod ${podName}...`]);
 
     try {
       // Execute a shell command (trying bash first, then sh)
@@ -220,13 +228,13 @@ export default function App() {
 
       const response = await tauri.execPodCommand(activeContext, {
         namespace: workload.namespace || 'default',
-        podName: workload.name,
+        podName,
         container: undefined, // Will use first container
-        command: ['/bin/sh', '-c', 'echo "=== Pod Shell ==="; echo "Pod: ' + workload.name + '"; echo "Image: ' + workload.image + '"; echo ""; ls -la']
+        command: ['/bin/sh', '-c', 'echo "=== Pod Shell ==="; echo "Pod: ' + podName + '"; echo "Image: ' + workload.image + '"; echo ""; ls -la']
       });
 
       if (response.error) {
-        setTerminalHistory(prev => [...prev, `Error: ${response.error}`]);
+        setTerminalHistory(prev => [...prev, `Error: ${response.error?.message}`]);
       } else {
         setTerminalHistory(prev => [...prev, response.output]);
       }
@@ -287,7 +295,8 @@ export default function App() {
               cpuUsage: 0,
               memUsage: 0,
               nodeCount: 0,
-              kubeconfig: config.kubeconfig // Store kubeconfig
+              kubeconfig: config.kubeconfig, // Store kubeconfig
+              contextName: config.contextName
             };
 
             const updatedClusters = [...clusters, newCluster];
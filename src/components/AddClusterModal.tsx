@@ -1,6 +1,7 @@
-import React, { useState } from 'react';
+import React, { useEffect, useState } from 'react';
 import { X } from 'lucide-react';
 import { NewClusterConfig, PROVIDERS, ProviderType, EnvType } from '../types';
+import { tauri, DiscoveredCluster } from '../lib/tauri';
 
 interface AddClusterModalProps {
     isOpen: boolean;
@@ -14,13 +15,37 @@ export const AddClusterModal = ({ isOpen, onClose, onAdd }: AddClusterModalProps
     const [region, setRegion] = useState('');
     const [environment, setEnvironment] = useState<EnvType>('dev');
     const [kubeconfig, setKubeconfig] = useState('');
+    const [contexts, setContexts] = useState<DiscoveredCluster[]>([]);
+    const [contextName, setContextName] = useState('');
+
+    // Offer a context choice when the pasted kubeconfig bundles several
+    useEffect(() => {
+        if (!kubeconfig.trim()) {
+            setContexts([]);
+            return;
+        }
+        tauri.listKubeconfigContexts(kubeconfig)
+            .then(found => {
+                setContexts(found);
+                const current = found.find(c => c.isCurrentContext) ?? found[0];
+                setContextName(current ? current.contextName : '');
+            })
+            .catch(() => setContexts([]));
+    }, [kubeconfig]);
 
     if (!isOpen) return null;
 
     const handleSubmit = (e: React.FormEvent) => {
         e.preventDefault();
         const finalRegion = provider === 'local' ? 'local' : region;
-        onAdd({ name, provider, region: finalRegion, environment, kubeconfig });
+        onAdd({
+            name,
+            provider,
+            region: finalRegion,
+            environment,
+            kubeconfig,
+            contextName: contexts.length > 1 ? contextName : undefined,
+        });
         onClose();
         // Reset form
         setName('');
@@ -28,6 +53,8 @@ export const AddClusterModal = ({ isOpen, onClose, onAdd }: AddClusterModalProps
         setRegion('');
         setEnvironment('dev');
         setKubeconfig('');
+        setContexts([]);
+        setContextName('');
     };
 
     return (
@@ -104,6 +131,21 @@ export const AddClusterModal = ({ isOpen, onClose, onAdd }: AddClusterModalProps
                         />
                     </div>
 
+                    {contexts.length > 1 && (
+                        <div>
+                            <label className="block text-xs font-bold text-slate-400 uppercase tracking-wider mb-2">Context</label>
+                            <select
+                                value={contextName}
+                                onChange={(e) => setContextName(e.target.value)}
+                                className="w-full bg-black/20 border border-white/10 rounded-lg px-4 py-2.5 text-white focus:outline-none focus:border-blue-500/50 focus:bg-black/40 transition-all duration-200 appearance-none"
+                            >
+                                {contexts.map(c => (
+                                    <option key={c.contextName} value={c.contextName} className="bg-slate-900">{c.contextName}</option>
+                                ))}
+                            </select>
+                        </div>
+                    )}
+
                     <div className="pt-4 flex justify-end gap-3 border-t border-white/5">
                         <button
                             type="button"
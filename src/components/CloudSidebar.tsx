@@ -69,6 +69,7 @@ export const CloudSidebar = ({
 
           {PROVIDERS.map(p => {
             const pClusters = clusters.filter(c => c.provider === p.id);
+            if (p.hideWhenEmpty && pClusters.length === 0) return null;
             const Icon = p.icon;
             return (
               <div key={p.id} className="mb-4">
@@ -1,17 +1,230 @@
-import { invoke } from '@tauri-apps/api/core';
-import type { ClusterContext, Workload } from '../types';
+import { invoke, Channel } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { ClusterContext, ProviderType, Workload } from '../types';
+
+export interface AppError {
+    kind:
+        | 'Unreachable'
+        | 'Tls'
+        | 'Unauthorized'
+        | 'Forbidden'
+        | 'NotFound'
+        | 'Timeout'
+        | 'AuthPluginFailed'
+        | 'ApiServerError'
+        | 'InvalidKubeconfig'
+        | 'InvalidRequest'
+        | 'Cancelled'
+        | 'Other';
+    message: string;
+    verb?: string;
+    resource?: string;
+    code?: number;
+}
 
 export interface ClusterInfo {
     status: 'Healthy' | 'Warning' | 'Critical' | 'Offline' | 'Connecting...' | 'Error';
+    statusReasons: string[];
     nodeCount: number;
     cpuUsage: number;
     memUsage: number;
-    error?: string;
+    usageSource: 'metrics' | 'requests' | 'unavailable';
+    serverVersion?: string;
+    serverPlatform?: string;
+    kubeletVersions: string[];
+    containerRuntimes: string[];
+    nodePlatforms: NodePlatform[];
+    distribution?: Distribution;
+    compatibilityWarning?: string;
+    error?: AppError;
+}
+
+export type Distribution = 'eks' | 'gke' | 'aks' | 'k3s' | 'kind' | 'openshift' | 'kubernetes';
+
+export interface NodePlatform {
+    os: string;
+    architecture: string;
+    count: number;
+}
+
+export interface NodeResources {
+    cpu: number;
+    memory: number;
+    pods: number;
+    ephemeralStorage: number;
+}
+
+export interface NodeTaint {
+    key: string;
+    value?: string;
+    effect: string;
+}
+
+export interface NodeCondition {
+    type: string;
+    status: string;
+    reason?: string;
+    message?: string;
+    lastTransitionTime?: string;
+}
+
+export interface NodeDetails {
+    name: string;
+    roles: string[];
+    internalIps: string[];
+    externalIps: string[];
+    instanceType?: string;
+    zone?: string;
+    region?: string;
+    kubeletVersion: string;
+    capacity: NodeResources;
+    allocatable: NodeResources;
+    taints: NodeTaint[];
+    conditions: NodeCondition[];
+    unschedulable: boolean;
+    podCount?: number;
+    creationTimestamp: string;
+}
+
+export interface DrainOptions {
+    deleteEmptyDirData?: boolean;
+    force?: boolean;
+    gracePeriodSeconds?: number;
+}
+
+export interface DrainProgress {
+    pod: string;
+    namespace: string;
+    status: 'skipped' | 'evicting' | 'blocked' | 'evicted' | 'failed';
+    message?: string;
+}
+
+export interface DrainSummary {
+    evicted: number;
+    skipped: number;
+}
+
+export interface ListOptions {
+    namespaces?: string[];
+    labelSelector?: string;
+    fieldSelector?: string;
+    limit?: number;
+    continue?: string;
+}
+
+export interface ScaleRequest {
+    kind: 'Deployment' | 'StatefulSet' | 'ReplicaSet';
+    namespace: string;
+    name: string;
+    replicas: number;
+    dryRun?: boolean;
+    ignoreHpa?: boolean;
+}
+
+export interface ScaleResult {
+    kind: string;
+    namespace: string;
+    name: string;
+    previousReplicas: number;
+    replicas: number;
+    dryRun: boolean;
 }
 
+export type RolloutKind = 'Deployment' | 'StatefulSet' | 'DaemonSet';
+
+export interface RolloutStatus {
+    message: string;
+    done: boolean;
+    desiredReplicas: number;
+    updatedReplicas: number;
+    readyReplicas: number;
+    availableReplicas: number;
+}
+
+export interface RolloutRevision {
+    revision: number;
+    name: string;
+    changeCause: string | null;
+    images: string[];
+    created: string | null;
+    current: boolean;
+}
+
+export interface RolloutUndo {
+    revision: number;
+    skipped: boolean;
+}
+
+export type DeletePropagation = 'Foreground' | 'Background' | 'Orphan';
+
+export interface DeleteRequest {
+    resourceType: string;
+    namespace?: string;
+    names: string[];
+    gracePeriodSeconds?: number;
+    propagationPolicy?: DeletePropagation;
+    force?: boolean;
+    dryRun?: boolean;
+}
+
+export type DeleteStatus = 'deleted' | 'terminating' | 'dryRun' | 'failed';
+
+export interface DeleteDependent {
+    kind: string;
+    name: string;
+    namespace: string | null;
+}
+
+export interface DeleteResult {
+    name: string;
+    namespace: string | null;
+    status: DeleteStatus;
+    dependents: DeleteDependent[];
+    error: AppError | null;
+}
+
+export interface LogOptions {
+    container?: string;
+    tailLines?: number;
+    sinceSeconds?: number;
+    sinceTime?: string;
+    timestamps?: boolean;
+    previous?: boolean;
+    limitBytes?: number;
+}
+
+export interface PodLogs {
+    container: string;
+    logs: string;
+    truncated: boolean;
+}
+
+export interface LogLine {
+    pod: string;
+    container: string;
+    timestamp: string | null;
+    message: string;
+}
+
+export interface LogTailRequest {
+    namespace: string;
+    labelSelector?: string;
+    deployment?: string;
+    include?: string[];
+    exclude?: string[];
+}
+
+export type LogEvent =
+    | { type: 'lines'; lines: LogLine[] }
+    | { type: 'started'; pod: string; container: string }
+    | { type: 'restarted'; pod: string; container: string; restartCount: number }
+    | { type: 'ended'; pod: string; container: string; reason: string }
+    | { type: 'error'; pod?: string; container?: string; error: AppError };
+
 export interface WorkloadsResponse {
     items: Workload[];
-    error?: string;
+    continue?: string;
+    error?: AppError;
 }
 
 export interface Resource {
@@ -21,18 +234,46 @@ export interface Resource {
     creationTimestamp: string;
 }
 
+export type ResourceEvent =
+    | { type: 'added'; object: Resource }
+    | { type: 'modified'; object: Resource }
+    | { type: 'deleted'; object: Resource }
+    | { type: 'reset'; namespace?: string; items: Resource[] }
+    | { type: 'error'; error: AppError };
+
 export interface ResourcesResponse {
     items: Resource[];
-    error?: string;
+    continue?: string;
+    error?: AppError;
 }
 
 export interface DiscoveredCluster {
     contextName: string;
     clusterName: string;
+    userName: string;
     server: string;
     namespace: string;
     isCurrentContext: boolean;
-    provider: 'local' | 'aws' | 'azure' | 'gcp';
+    provider: ProviderType;
+    region?: string;
+    account?: string;
+    providerClusterName?: string;
+    sourceFile?: string;
+}
+
+export interface ClusterProbe {
+    contextName: string;
+    reachable: boolean;
+    serverVersion: string | null;
+    auth: 'authenticated' | 'rejected' | 'pluginFailed' | 'notChecked';
+    latencyMs: number;
+    error?: AppError;
+}
+
+export interface KubeconfigChange {
+    added: DiscoveredCluster[];
+    removed: DiscoveredCluster[];
+    changed: DiscoveredCluster[];
 }
 
 export interface ExecRequest {
@@ -44,7 +285,23 @@ export interface ExecRequest {
 
 export interface ExecResponse {
     output: string;
-    error?: string;
+    error?: AppError;
+}
+
+export interface DiagnosticStep {
+    id: 'kubeconfig' | 'dns' | 'tcp' | 'tls' | 'auth' | 'version' | 'readyz' | 'rbac';
+    label: string;
+    status: 'passed' | 'failed' | 'skipped';
+    durationMs: number;
+    detail?: string;
+    error?: AppError;
+}
+
+export interface DiagnosticReport {
+    server: string | null;
+    steps: DiagnosticStep[];
+    failedStep: string | null;
+    totalMs: number;
 }
 
 // Tauri API wrapper functions
@@ -53,17 +310,110 @@ export const tauri = {
         return await invoke<ClusterInfo>('get_cluster_info', { config });
     },
 
-    async getWorkloads(config: ClusterContext): Promise<WorkloadsResponse> {
-        return await invoke<WorkloadsResponse>('get_workloads', { config });
+    async getWorkloads(config: ClusterContext, options?: ListOptions): Promise<WorkloadsResponse> {
+        return await invoke<WorkloadsResponse>('get_workloads', { config, options });
+    },
+
+    async scaleWorkload(config: ClusterContext, request: ScaleRequest): Promise<ScaleResult> {
+        return await invoke<ScaleResult>('scale_workload', { config, request });
+    },
+
+    async rolloutRestart(config: ClusterContext, kind: RolloutKind, namespace: string, name: string): Promise<void> {
+        return await invoke<void>('rollout_restart', { config, kind, namespace, name });
+    },
+
+    async rolloutStatus(
+        config: ClusterContext,
+        kind: RolloutKind,
+        namespace: string,
+        name: string,
+        onProgress: (status: RolloutStatus) => void,
+        timeoutSeconds?: number,
+    ): Promise<RolloutStatus> {
+        const channel = new Channel<RolloutStatus>();
+        channel.onmessage = onProgress;
+        return await invoke<RolloutStatus>('rollout_status', {
+            config,
+            kind,
+            namespace,
+            name,
+            timeoutSeconds,
+            onProgress: channel,
+        });
+    },
+
+    async rolloutHistory(config: ClusterContext, kind: RolloutKind, namespace: string, name: string): Promise<RolloutRevision[]> {
+        return await invoke<RolloutRevision[]>('rollout_history', { config, kind, namespace, name });
+    },
+
+    async rolloutUndo(
+        config: ClusterContext,
+        kind: RolloutKind,
+        namespace: string,
+        name: string,
+        toRevision?: number,
+    ): Promise<RolloutUndo> {
+        return await invoke<RolloutUndo>('rollout_undo', { config, kind, namespace, name, toRevision });
+    },
+
+    async getNodes(config: ClusterContext): Promise<NodeDetails[]> {
+        return await invoke<NodeDetails[]>('get_nodes', { config });
+    },
+
+    async cordonNode(config: ClusterContext, nodeName: string): Promise<void> {
+        return await invoke<void>('cordon_node', { config, nodeName });
+    },
+
+    async uncordonNode(config: ClusterContext, nodeName: string): Promise<void> {
+        return await invoke<void>('uncordon_node', { config, nodeName });
+    },
+
+    async drainNode(
+        config: ClusterContext,
+        nodeName: string,
+        onProgress: (progress: DrainProgress) => void,
+        options?: DrainOptions,
+    ): Promise<DrainSummary> {
+        const channel = new Channel<DrainProgress>();
+        channel.onmessage = onProgress;
+        return await invoke<DrainSummary>('drain_node', { config, nodeName, options, onProgress: channel });
+    },
+
+    async cancelDrain(config: ClusterContext, nodeName: string): Promise<boolean> {
+        return await invoke<boolean>('cancel_drain', { config, nodeName });
     },
 
-    async getResources(config: ClusterContext, resourceType: string): Promise<ResourcesResponse> {
+    async getResources(config: ClusterContext, resourceType: string, options?: ListOptions): Promise<ResourcesResponse> {
         return await invoke<ResourcesResponse>('get_resources', {
             config,
-            resourceType
+            resourceType,
+            options
         });
     },
 
+    async deleteResource(config: ClusterContext, request: DeleteRequest): Promise<DeleteResult[]> {
+        return await invoke<DeleteResult[]>('delete_resource', { config, request });
+    },
+
+    async subscribeResources(
+        config: ClusterContext,
+        resourceType: string,
+        onEvents: (events: ResourceEvent[]) => void,
+        options?: ListOptions,
+    ): Promise<string> {
+        const channel = new Channel<ResourceEvent[]>();
+        channel.onmessage = onEvents;
+        return await invoke<string>('subscribe_resources', { config, resourceType, options, onEvent: channel });
+    },
+
+    async unsubscribeResources(subscriptionId: string): Promise<boolean> {
+        return await invoke<boolean>('unsubscribe_resources', { subscriptionId });
+    },
+
+    async diagnoseCluster(config: ClusterContext): Promise<DiagnosticReport> {
+        return await invoke<DiagnosticReport>('diagnose_cluster', { config });
+    },
+
     async saveClusters(clusters: ClusterContext[]): Promise<void> {
         return await invoke('save_clusters', { clusters });
     },
@@ -72,8 +422,50 @@ export const tauri = {
         return await invoke<ClusterContext[]>('load_clusters');
     },
 
-    async discoverClusters(): Promise<DiscoveredCluster[]> {
-        return await invoke<DiscoveredCluster[]>('discover_clusters');
+    async discoverClusters(extraPaths?: string[]): Promise<DiscoveredCluster[]> {
+        return await invoke<DiscoveredCluster[]>('discover_clusters', { extraPaths });
+    },
+
+    async probeDiscoveredClusters(
+        onResult: (probe: ClusterProbe) => void,
+        extraPaths?: string[],
+        timeoutSecs?: number,
+    ): Promise<ClusterProbe[]> {
+        const channel = new Channel<ClusterProbe>();
+        channel.onmessage = onResult;
+        return await invoke<ClusterProbe[]>('probe_discovered_clusters', { extraPaths, timeoutSecs, onResult: channel });
+    },
+
+    async importDiscoveredCluster(contextName: string, extraPaths?: string[]): Promise<ClusterContext> {
+        return await invoke<ClusterContext>('import_discovered_cluster', { contextName, extraPaths });
+    },
+
+    async renameKubeconfigContext(oldName: string, newName: string, extraPaths?: string[]): Promise<DiscoveredCluster[]> {
+        return await invoke<DiscoveredCluster[]>('rename_kubeconfig_context', { oldName, newName, extraPaths });
+    },
+
+    async deleteKubeconfigContext(contextName: string, extraPaths?: string[]): Promise<DiscoveredCluster[]> {
+        return await invoke<DiscoveredCluster[]>('delete_kubeconfig_context', { contextName, extraPaths });
+    },
+
+    async setKubeconfigContextNamespace(contextName: string, namespace: string, extraPaths?: string[]): Promise<DiscoveredCluster[]> {
+        return await invoke<DiscoveredCluster[]>('set_kubeconfig_context_namespace', { contextName, namespace, extraPaths });
+    },
+
+    async useKubeconfigContext(contextName: string, extraPaths?: string[]): Promise<DiscoveredCluster[]> {
+        return await invoke<DiscoveredCluster[]>('use_kubeconfig_context', { contextName, extraPaths });
+    },
+
+    async watchKubeconfigs(extraPaths?: string[]): Promise<void> {
+        return await invoke('watch_kubeconfigs', { extraPaths });
+    },
+
+    async onKubeconfigChanged(handler: (change: KubeconfigChange) => void): Promise<UnlistenFn> {
+        return await listen<KubeconfigChange>('kubeconfig-changed', event => handler(event.payload));
+    },
+
+    async listKubeconfigContexts(kubeconfig: string): Promise<DiscoveredCluster[]> {
+        return await invoke<DiscoveredCluster[]>('list_kubeconfig_contexts', { kubeconfig });
     },
 
     async execPodCommand(config: ClusterContext, request: ExecRequest): Promise<ExecResponse> {
@@ -83,4 +475,35 @@ export const tauri = {
     async getPodContainers(config: ClusterContext, namespace: string, podName: string): Promise<string[]> {
         return await invoke<string[]>('get_pod_containers', { config, namespace, podName });
     },
+
+    async getPodLogs(config: ClusterContext, namespace: string, podName: string, options?: LogOptions): Promise<PodLogs> {
+        return await invoke<PodLogs>('get_pod_logs', { config, namespace, podName, options });
+    },
+
+    async followPodLogs(
+        config: ClusterContext,
+        namespace: string,
+        podName: string,
+        onEvent: (event: LogEvent) => void,
+        options?: LogOptions,
+    ): Promise<string> {
+        const channel = new Channel<LogEvent>();
+        channel.onmessage = onEvent;
+        return await invoke<string>('follow_pod_logs', { config, namespace, podName, options, onEvent: channel });
+    },
+
+    async tailPodLogs(
+        config: ClusterContext,
+        request: LogTailRequest,
+        onEvent: (event: LogEvent) => void,
+        options?: LogOptions,
+    ): Promise<string> {
+        const channel = new Channel<LogEvent>();
+        channel.onmessage = onEvent;
+        return await invoke<string>('tail_pod_logs', { config, request, options, onEvent: channel });
+    },
+
+    async stopPodLogs(subscriptionId: string): Promise<boolean> {
+        return await invoke<boolean>('stop_pod_logs', { subscriptionId });
+    },
 };
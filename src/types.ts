@@ -1,5 +1,5 @@
 import {
-  Laptop, Cloud, CloudLightning, Globe
+  Laptop, Cloud, CloudLightning, Globe, Server, Ship, Droplet, Box, Container, Boxes
 } from 'lucide-react';
 
 /* ==================================================================================================================
@@ -7,7 +7,9 @@ import {
    Description: Core domain definitions and mock data.
    ================================================================================================================== */
 
-export type ProviderType = 'aws' | 'azure' | 'gcp' | 'local';
+export type ProviderType =
+  | 'aws' | 'azure' | 'gcp' | 'local'
+  | 'openshift' | 'rancher' | 'digitalocean' | 'kind' | 'minikube' | 'k3d';
 export type EnvType = 'prod' | 'dev' | 'local';
 
 export interface CloudProvider {
@@ -16,6 +18,8 @@ export interface CloudProvider {
   icon: any;
   color: string;
   authCommand: string;
+  // Only listed in the sidebar once a cluster of this type exists
+  hideWhenEmpty?: boolean;
 }
 
 export interface ClusterContext {
@@ -29,6 +33,9 @@ export interface ClusterContext {
   memUsage: number;
   nodeCount: number;
   kubeconfig?: string;
+  contextName?: string;
+  clusterName?: string;
+  userName?: string;
 }
 
 export interface NewClusterConfig {
@@ -37,16 +44,37 @@ export interface NewClusterConfig {
   region: string;
   environment: EnvType;
   kubeconfig?: string;
+  contextName?: string;
+}
+
+export interface WorkloadPod {
+  name: string;
+  status: string;
+  ready: boolean;
+  readyContainers: string;
+  restarts: number;
+  lastRestart?: string;
+  lastTerminationReason?: string;
+  lastExitCode?: number;
+  nodeName?: string;
+  age: string;
 }
 
 export interface Workload {
   id: string;
   name: string;
   namespace: string;
+  kind: 'Deployment' | 'StatefulSet' | 'DaemonSet' | 'ReplicaSet' | 'Job' | 'CronJob' | 'Pod';
   image: string;
+  images: string[];
   contextId: string;
-  status: 'Running' | 'Pending' | 'Failed' | 'CrashLoopBackOff';
+  status: string;
   replicas: number;
+  readyReplicas: number;
+  updatedReplicas?: number;
+  availableReplicas?: number;
+  selector?: string;
+  pods: WorkloadPod[];
   uptime: string;
 }
 
@@ -55,4 +83,10 @@ export const PROVIDERS: CloudProvider[] = [
   { id: 'aws', label: 'Amazon EKS', icon: CloudLightning, color: 'text-orange-400', authCommand: 'aws-vault exec' },
   { id: 'azure', label: 'Azure AKS', icon: Cloud, color: 'text-blue-400', authCommand: 'az account set' },
   { id: 'gcp', label: 'Google GKE', icon: Globe, color: 'text-emerald-400', authCommand: 'gcloud config set project' },
+  { id: 'openshift', label: 'OpenShift', icon: Server, color: 'text-red-400', authCommand: 'oc login', hideWhenEmpty: true },
+  { id: 'rancher', label: 'Rancher', icon: Ship, color: 'text-sky-400', authCommand: 'rancher login', hideWhenEmpty: true },
+  { id: 'digitalocean', label: 'DigitalOcean', icon: Droplet, color: 'text-blue-300', authCommand: 'doctl auth init', hideWhenEmpty: true },
+  { id: 'kind', label: 'kind', icon: Box, color: 'text-slate-300', authCommand: 'kind export kubeconfig', hideWhenEmpty: true },
+  { id: 'minikube', label: 'minikube', icon: Container, color: 'text-slate-300', authCommand: 'minikube update-context', hideWhenEmpty: true },
+  { id: 'k3d', label: 'k3d', icon: Boxes, color: 'text-slate-300', authCommand: 'k3d kubeconfig merge', hideWhenEmpty: true },
 ];
\ No newline at end of file